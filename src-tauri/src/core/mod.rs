@@ -5,9 +5,19 @@
 pub mod process_manager;
 
 pub use process_manager::{
-    add_process_fork, add_process_import, auto_start_processes_on_init, clear_process_output,
-    create_process_manager, get_process, get_process_output, kill_all_processes, list_processes,
-    remove_process, start_auto_start_processes, start_process, stop_process, update_process,
-    CommandType, ProcessConfig, ProcessInfo, ProcessManager, ProcessMode, ProcessOutput,
-    ProcessStatus,
+    add_process_fork, add_process_import, add_process_schedule, adopt_running_processes,
+    auto_start_processes_on_init, clear_process_output, clear_process_quarantine,
+    create_process_manager, get_process, get_process_metrics, get_process_output,
+    list_process_runs, list_process_schedules, list_processes, list_schedule_runs, remove_process,
+    remove_process_schedule, resize_process_pty, send_process_input, shutdown_processes,
+    start_auto_start_processes, start_metrics_sampler, start_process, start_scheduler,
+    start_supervisor, stop_process, stop_process_with_dependents, stop_processes_on_exit,
+    subscribe_process_events, update_process, update_process_schedule, watch_adopted_processes,
+    CommandType, ExitInfo, ExitReason, HealthCheckConfig, HealthProbe, HealthState, HookCommand,
+    HookFailure, MetricsResolution, MissedRunPolicy, ProcessConfig, ProcessEvent, ProcessEventBus,
+    ProcessEventMessage, ProcessInfo, ProcessInstance, ProcessKind, ProcessManager, ProcessMetrics,
+    ProcessMetricsEvent, ProcessMode, ProcessOptions, ProcessOutput, ProcessRun, ProcessSchedule,
+    ProcessStatus, PtySize, ReadinessConfig, ReadinessProbe, ResourceLimits, RestartPolicy,
+    ScheduleAction, ScheduleRun, ScheduleRunStatus, ShutdownProgress, StaleCleanup, StopSignal,
+    ThresholdAction, ThresholdMetric, ThresholdRule, ThresholdTrigger,
 };
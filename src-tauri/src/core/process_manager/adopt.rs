@@ -0,0 +1,139 @@
+//! 重新接管运行中的进程
+//!
+//! 每个运行实例启动后将 PID 和进程启动时间记录到数据库，退出后删除记录。
+//! 应用崩溃或升级重启后，仍在运行且启动时间一致的进程会被重新接管（adopted），
+//! 继续跟踪其运行状态并支持停止，不会重复启动。
+//! 上次运行时的输出管道已随旧实例关闭，接管后无法再捕获其输出（脱离模式的进程除外，
+//! 其输出写入日志文件）
+
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use super::detach::follow_log_files;
+use super::events::emit_process_event;
+use super::handle::{is_same_process, process_start_time, ProcessHandle};
+use super::health::start_health_check;
+use super::process_tree::ProcessTree;
+use super::readiness::mark_ready;
+use super::state::{ProcessManager, ProcessManagerState, RunningProcess};
+use super::types::{HealthState, ProcessEvent, ProcessInstance};
+use super::utils::current_timestamp;
+use super::waiter::ExitWaiter;
+use crate::storage::{save_process_instance, DbState};
+
+/// 记录运行实例，供应用重启后重新接管
+pub(super) fn record_instance(
+    app: &AppHandle,
+    id: &str,
+    pid: u32,
+    started_at: i64,
+    detached: bool,
+) {
+    // 无法获取启动时间时不能确认 PID 是否被复用，不记录
+    let Some(start_time) = process_start_time(pid) else {
+        return;
+    };
+    let instance = ProcessInstance {
+        process_id: id.to_string(),
+        pid,
+        start_time,
+        started_at,
+        detached,
+    };
+    if let Some(db) = app.try_state::<DbState>() {
+        if let Ok(conn) = db.0.lock() {
+            if let Err(e) = save_process_instance(&conn, &instance) {
+                eprintln!("Failed to save instance of process {}: {}", id, e);
+            }
+        }
+    }
+}
+
+/// 重新接管上次运行时启动、仍在运行的进程（应用启动时加载配置后调用）
+///
+/// 返回已不再运行或配置已删除的实例记录，由调用方从数据库中删除
+pub fn adopt_running_processes(
+    state: &mut ProcessManagerState,
+    instances: Vec<ProcessInstance>,
+) -> Vec<ProcessInstance> {
+    let mut stale = Vec::new();
+    for instance in instances {
+        let id = instance.process_id.clone();
+        let config = match state.configs.get(&id) {
+            Some(config)
+                if !state.processes.contains_key(&id)
+                    && is_same_process(instance.pid, instance.start_time) =>
+            {
+                config.clone()
+            }
+            _ => {
+                stale.push(instance);
+                continue;
+            }
+        };
+
+        let uptime = Duration::from_secs((current_timestamp() - instance.started_at).max(0) as u64);
+        let running = RunningProcess {
+            child: ProcessHandle::Adopted {
+                pid: instance.pid,
+                start_time: instance.start_time,
+            },
+            tree: ProcessTree::adopt(instance.pid),
+            config,
+            started_at: instance.started_at,
+            launched_at: Instant::now()
+                .checked_sub(uptime)
+                .unwrap_or_else(Instant::now),
+            detached: instance.detached,
+            stdin: None,
+            pty: None,
+            cgroup: None,
+        };
+        println!("Adopted running process {} (PID {})", id, instance.pid);
+        state.processes.insert(id.clone(), running);
+        let runtime = state.runtime.entry(id).or_default();
+        runtime.health = HealthState::Unknown;
+        runtime.health_failures = 0;
+        runtime.ready = false;
+    }
+    stale
+}
+
+/// 开始跟踪重新接管的进程：等待其退出并按配置执行健康检查
+///
+/// 需在进程管理器注册到应用之后调用。接管的进程已运行过一段时间，直接视为就绪
+pub fn watch_adopted_processes(app: &AppHandle, manager: &ProcessManager) {
+    let adopted: Vec<(String, u32, u64, bool, bool)> = match manager.lock() {
+        Ok(state) => state
+            .processes
+            .iter()
+            .filter_map(|(id, running)| match running.child {
+                ProcessHandle::Adopted { pid, start_time } => Some((
+                    id.clone(),
+                    pid,
+                    start_time,
+                    running.detached,
+                    running.config.health_check.is_some(),
+                )),
+                ProcessHandle::Spawned(_) => None,
+            })
+            .collect(),
+        Err(_) => return,
+    };
+
+    for (id, pid, start_time, detached, has_health_check) in adopted {
+        emit_process_event(app, &id, ProcessEvent::Started { pid });
+        mark_ready(app, manager, &id, pid);
+        // 脱离模式的进程输出写入日志文件，接管后继续跟踪
+        if detached {
+            follow_log_files(app, manager, &id, pid);
+        }
+        match ExitWaiter::adopted(pid, start_time) {
+            Some(waiter) => waiter.start(app, manager, &id),
+            None => eprintln!("Failed to watch adopted process {} for exit", id),
+        }
+        if has_health_check {
+            start_health_check(app, manager, &id, pid);
+        }
+    }
+}
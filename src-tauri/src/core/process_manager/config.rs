@@ -1,26 +1,37 @@
 //! 进程配置管理命令
 
+use regex::Regex;
 use std::collections::HashMap;
 use std::path::Path;
 use tauri::AppHandle;
 
+use super::dependencies::validate_dependencies;
+use super::events::emit_process_event;
+use super::lifecycle::stop_process_with_manager;
+use super::stale::remove_pid_file;
 use super::state::{ProcessManager, ProcessOutput};
-use super::types::{CommandType, ProcessConfig, ProcessMode};
+use super::types::{
+    CommandType, HealthCheckConfig, HealthProbe, HookCommand, ProcessConfig, ProcessEvent,
+    ProcessMode, ProcessOptions, ReadinessConfig, ReadinessProbe, ResourceLimits, RestartPolicy,
+    StaleCleanup,
+};
 use super::utils::{copy_dir_recursive, current_timestamp, get_processes_dir};
 use crate::storage::{delete_process_config, save_process_config, DbState};
 
 /// Fork 模式添加进程
 #[tauri::command]
 pub fn add_process_fork(
+    app: AppHandle,
     state: tauri::State<ProcessManager>,
     db_state: tauri::State<DbState>,
     name: String,
     working_dir: String,
     executable_path: Option<String>,
     args: Vec<String>,
-    auto_restart: bool,
+    restart_policy: Option<RestartPolicy>,
     auto_start: bool,
     command_type: Option<String>,
+    options: Option<ProcessOptions>,
 ) -> Result<ProcessConfig, String> {
     // 解析命令类型
     let cmd_type = match command_type.as_deref() {
@@ -54,7 +65,7 @@ pub fn add_process_fork(
     let command = executable_path.unwrap_or_default();
 
     let id = uuid::Uuid::new_v4().to_string();
-    let config = ProcessConfig {
+    let mut config = ProcessConfig {
         id: id.clone(),
         name,
         mode: ProcessMode::Fork,
@@ -64,10 +75,19 @@ pub fn add_process_fork(
         working_dir,
         source_path: None,
         env: HashMap::new(),
-        auto_restart,
+        restart_policy: restart_policy.unwrap_or_default(),
         auto_start,
         created_at: current_timestamp(),
+        ..Default::default()
     };
+    if let Some(options) = options {
+        options.apply_to(&mut config);
+    }
+    validate_config(&config)?;
+    {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        validate_dependencies(&manager.configs, &config)?;
+    }
 
     // 保存到数据库
     {
@@ -76,9 +96,13 @@ pub fn add_process_fork(
     }
 
     // 添加到内存
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
-    manager.configs.insert(id.clone(), config.clone());
-    manager.outputs.insert(id, ProcessOutput::default());
+    {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        manager.configs.insert(id.clone(), config.clone());
+        manager.outputs.insert(id.clone(), ProcessOutput::default());
+    }
+
+    emit_process_event(&app, &id, ProcessEvent::ConfigChanged);
     Ok(config)
 }
 
@@ -92,9 +116,10 @@ pub fn add_process_import(
     source_folder: String,
     executable_path: Option<String>,
     args: Vec<String>,
-    auto_restart: bool,
+    restart_policy: Option<RestartPolicy>,
     auto_start: bool,
     command_type: Option<String>,
+    options: Option<ProcessOptions>,
 ) -> Result<ProcessConfig, String> {
     // 解析命令类型
     let cmd_type = match command_type.as_deref() {
@@ -129,7 +154,7 @@ pub fn add_process_import(
     // command 字段：如果提供了 executable_path 则使用它，否则为空字符串
     let command = executable_path.unwrap_or_default();
 
-    let config = ProcessConfig {
+    let mut config = ProcessConfig {
         id: id.clone(),
         name,
         mode: ProcessMode::Import,
@@ -139,10 +164,19 @@ pub fn add_process_import(
         working_dir: target_dir.to_string_lossy().to_string(),
         source_path: Some(source_folder),
         env: HashMap::new(),
-        auto_restart,
+        restart_policy: restart_policy.unwrap_or_default(),
         auto_start,
         created_at: current_timestamp(),
+        ..Default::default()
     };
+    if let Some(options) = options {
+        options.apply_to(&mut config);
+    }
+    validate_config(&config)?;
+    {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        validate_dependencies(&manager.configs, &config)?;
+    }
 
     // 保存到数据库
     {
@@ -151,45 +185,93 @@ pub fn add_process_import(
     }
 
     // 添加到内存
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
-    manager.configs.insert(id.clone(), config.clone());
-    manager.outputs.insert(id, ProcessOutput::default());
+    {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        manager.configs.insert(id.clone(), config.clone());
+        manager.outputs.insert(id.clone(), ProcessOutput::default());
+    }
+
+    emit_process_event(&app, &id, ProcessEvent::ConfigChanged);
     Ok(config)
 }
 
 /// 删除进程配置
+///
+/// 运行中的进程先按停止流程停止（执行停止前后的钩子并记录退出），停止失败时不删除
 #[tauri::command]
-pub fn remove_process(
-    _app: AppHandle,
-    state: tauri::State<ProcessManager>,
-    db_state: tauri::State<DbState>,
+pub async fn remove_process(
+    app: AppHandle,
+    state: tauri::State<'_, ProcessManager>,
+    db_state: tauri::State<'_, DbState>,
     id: String,
 ) -> Result<(), String> {
+    // 被其他进程依赖的进程不能删除
+    let running = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        let required_by: Vec<&str> = manager
+            .configs
+            .values()
+            .filter(|c| c.depends_on.contains(&id))
+            .map(|c| c.name.as_str())
+            .collect();
+        if !required_by.is_empty() {
+            return Err(format!(
+                "Process is required by: {}",
+                required_by.join(", ")
+            ));
+        }
+        if !manager.configs.contains_key(&id) {
+            return Err("Process not found".to_string());
+        }
+        manager.processes.contains_key(&id)
+    };
+
+    // 等待进程退出可能耗时较长，在阻塞线程中执行
+    if running {
+        let manager = state.inner().clone();
+        let stop_app = app.clone();
+        let stop_id = id.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            stop_process_with_manager(&stop_app, &manager, &stop_id)
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        // 进程可能在此期间已自行退出
+        if let Err(e) = result {
+            if e != "Process is not running" {
+                return Err(e);
+            }
+        }
+    }
+
+    {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        if manager.processes.contains_key(&id) || manager.starting.contains(&id) {
+            return Err("Process was started again while being removed".to_string());
+        }
+        manager.runtime.remove(&id);
+        manager
+            .configs
+            .remove(&id)
+            .ok_or_else(|| "Process not found".to_string())?;
+        manager.outputs.remove(&id);
+    }
+
     // 从数据库删除
     {
         let conn = db_state.0.lock().map_err(|e| e.to_string())?;
         delete_process_config(&conn, &id)?;
     }
 
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
-
-    if let Some(mut running) = manager.processes.remove(&id) {
-        let _ = running.child.kill();
-    }
-
-    let _config = manager
-        .configs
-        .remove(&id)
-        .ok_or_else(|| "Process not found".to_string())?;
-
-    manager.outputs.remove(&id);
-
+    remove_pid_file(&app, &id);
+    emit_process_event(&app, &id, ProcessEvent::Removed);
     Ok(())
 }
 
 /// 编辑进程配置
 #[tauri::command]
 pub fn update_process(
+    app: AppHandle,
     state: tauri::State<ProcessManager>,
     db_state: tauri::State<DbState>,
     id: String,
@@ -197,9 +279,10 @@ pub fn update_process(
     working_dir: Option<String>,
     executable_path: Option<String>,
     args: Vec<String>,
-    auto_restart: bool,
+    restart_policy: Option<RestartPolicy>,
     auto_start: bool,
     command_type: Option<String>,
+    options: Option<ProcessOptions>,
 ) -> Result<ProcessConfig, String> {
     // 解析命令类型
     let cmd_type = match command_type.as_deref() {
@@ -244,16 +327,21 @@ pub fn update_process(
 
     let mut manager = state.lock().map_err(|e| e.to_string())?;
 
-    let config = manager
+    let mut config = manager
         .configs
-        .get_mut(&id)
-        .ok_or_else(|| "Process not found".to_string())?;
+        .get(&id)
+        .ok_or_else(|| "Process not found".to_string())?
+        .clone();
 
     config.name = name;
     config.args = args;
-    config.auto_restart = auto_restart;
     config.auto_start = auto_start;
 
+    // 更新重启策略（如果提供）
+    if let Some(policy) = restart_policy {
+        config.restart_policy = policy;
+    }
+
     // 更新命令类型（如果提供）
     if let Some(ct) = cmd_type {
         config.command_type = ct;
@@ -269,9 +357,167 @@ pub fn update_process(
         config.command = exe_path;
     }
 
+    // 更新高级选项（如果提供）
+    if let Some(options) = options {
+        options.apply_to(&mut config);
+    }
+    validate_config(&config)?;
+    validate_dependencies(&manager.configs, &config)?;
+
     // 保存到数据库
-    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
-    save_process_config(&conn, config)?;
+    {
+        let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+        save_process_config(&conn, &config)?;
+    }
 
-    Ok(config.clone())
+    // 关闭自动重启时取消待执行的重启
+    if !config.restart_policy.is_enabled() {
+        if let Some(runtime) = manager.runtime.get_mut(&id) {
+            runtime.reset_restart();
+        }
+    }
+    manager.configs.insert(id.clone(), config.clone());
+    drop(manager);
+
+    emit_process_event(&app, &id, ProcessEvent::ConfigChanged);
+    Ok(config)
+}
+
+/// 校验进程配置
+fn validate_config(config: &ProcessConfig) -> Result<(), String> {
+    if config.restart_delay_ms == 0 {
+        return Err("Restart delay must be greater than 0".to_string());
+    }
+    if config.restart_max_delay_ms < config.restart_delay_ms {
+        return Err("Maximum restart delay must not be less than the restart delay".to_string());
+    }
+    if config.crash_loop_max_exits > 0 && config.crash_loop_window_secs == 0 {
+        return Err("Crash loop window must be greater than 0".to_string());
+    }
+    if let Some(check) = &config.health_check {
+        validate_health_check(check)?;
+    }
+    if let Some(readiness) = &config.readiness {
+        validate_readiness(readiness, config.health_check.is_some())?;
+    }
+    config
+        .pre_start
+        .iter()
+        .chain(&config.post_start)
+        .chain(&config.pre_stop)
+        .chain(&config.post_stop)
+        .try_for_each(validate_hook)?;
+    validate_resource_limits(&config.resource_limits)?;
+    if config
+        .threshold_rules
+        .iter()
+        .any(|rule| !rule.above.is_finite() || rule.above <= 0.0)
+    {
+        return Err("Threshold must be greater than 0".to_string());
+    }
+    if config
+        .threshold_rules
+        .iter()
+        .any(|rule| rule.duration_secs == 0)
+    {
+        return Err("Threshold duration must be greater than 0".to_string());
+    }
+    if config.task_timeout_ms == 0 {
+        return Err("Task timeout must be greater than 0".to_string());
+    }
+    if config.use_pty {
+        if cfg!(windows) {
+            return Err("PTY mode is only supported on Unix".to_string());
+        }
+        if config.pty_size.rows == 0 || config.pty_size.cols == 0 {
+            return Err("PTY rows and columns must be greater than 0".to_string());
+        }
+    }
+    if config.stale_cleanup == StaleCleanup::ExePath
+        && config.command_type != CommandType::Executable
+    {
+        return Err("Stale cleanup by executable path requires an executable command".to_string());
+    }
+    Ok(())
+}
+
+/// 校验资源限制：设置的限制必须大于 0（0 会让进程无法运行）
+fn validate_resource_limits(limits: &ResourceLimits) -> Result<(), String> {
+    if limits.max_memory_bytes == Some(0) {
+        return Err("Memory limit must be greater than 0".to_string());
+    }
+    if limits.cpu_quota_percent == Some(0) {
+        return Err("CPU quota must be greater than 0".to_string());
+    }
+    if limits.max_open_files == Some(0) {
+        return Err("Open files limit must be greater than 0".to_string());
+    }
+    if limits.max_processes == Some(0) {
+        return Err("Process limit must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// 校验钩子命令
+fn validate_hook(hook: &HookCommand) -> Result<(), String> {
+    if hook.command.trim().is_empty() {
+        return Err("Hook command must not be empty".to_string());
+    }
+    if hook.timeout_ms == 0 {
+        return Err("Hook timeout must be greater than 0".to_string());
+    }
+    Ok(())
+}
+
+/// 校验就绪条件
+fn validate_readiness(readiness: &ReadinessConfig, has_health_check: bool) -> Result<(), String> {
+    if readiness.startup_timeout_ms == 0 {
+        return Err("Startup timeout must be greater than 0".to_string());
+    }
+    match &readiness.probe {
+        ReadinessProbe::OutputMatch { pattern } => {
+            Regex::new(pattern).map_err(|e| format!("Invalid readiness pattern: {}", e))?;
+        }
+        ReadinessProbe::Tcp { host, port } => {
+            if host.trim().is_empty() || *port == 0 {
+                return Err("Readiness check requires a host and a non-zero port".to_string());
+            }
+        }
+        ReadinessProbe::HealthCheck => {
+            if !has_health_check {
+                return Err("Readiness by health check requires a health check".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 校验健康检查配置
+fn validate_health_check(check: &HealthCheckConfig) -> Result<(), String> {
+    if check.interval_ms == 0 || check.timeout_ms == 0 {
+        return Err("Health check interval and timeout must be greater than 0".to_string());
+    }
+    if check.failure_threshold == 0 {
+        return Err("Health check failure threshold must be greater than 0".to_string());
+    }
+    match &check.probe {
+        HealthProbe::Tcp { host, port } => {
+            if host.trim().is_empty() || *port == 0 {
+                return Err("Health check requires a host and a non-zero port".to_string());
+            }
+        }
+        HealthProbe::Http { url, .. } => {
+            let parsed =
+                url::Url::parse(url).map_err(|e| format!("Invalid health check URL: {}", e))?;
+            if parsed.scheme() != "http" {
+                return Err("Health check URL must use http".to_string());
+            }
+        }
+        HealthProbe::Command { command, .. } => {
+            if command.trim().is_empty() {
+                return Err("Health check command must not be empty".to_string());
+            }
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,142 @@
+//! 崩溃循环检测
+//!
+//! 自动重启的进程在时间窗口内退出次数过多时，停止重启并将其隔离（状态为 Error），
+//! 直到用户手动解除隔离
+
+use tauri::AppHandle;
+
+use super::events::emit_process_event;
+use super::state::{ProcessManager, ProcessRuntime};
+use super::types::{ProcessConfig, ProcessEvent, ProcessQuarantinedEvent, QuarantineInfo};
+
+/// 记录一次需要自动重启的退出，并检测是否进入崩溃循环
+///
+/// 检测到崩溃循环时取消待执行的重启、隔离进程，并返回需要发送的隔离事件
+pub(super) fn record_exit(
+    runtime: &mut ProcessRuntime,
+    config: &ProcessConfig,
+    now: i64,
+) -> Option<ProcessQuarantinedEvent> {
+    if config.crash_loop_max_exits == 0 {
+        return None;
+    }
+
+    let window_start = now - config.crash_loop_window_secs as i64;
+    runtime.recent_exits.push_back(now);
+    while runtime
+        .recent_exits
+        .front()
+        .is_some_and(|&t| t < window_start)
+    {
+        runtime.recent_exits.pop_front();
+    }
+
+    let exits = runtime.recent_exits.len() as u32;
+    if exits <= config.crash_loop_max_exits {
+        return None;
+    }
+
+    let reason = format!(
+        "Crash loop detected: exited {} times within {} seconds",
+        exits, config.crash_loop_window_secs
+    );
+    runtime.next_restart_at = None;
+    runtime.quarantine = Some(QuarantineInfo {
+        reason: reason.clone(),
+        since: now,
+    });
+
+    Some(ProcessQuarantinedEvent {
+        id: config.id.clone(),
+        reason,
+        exits,
+        window_secs: config.crash_loop_window_secs,
+    })
+}
+
+/// 解除进程的崩溃循环隔离
+#[tauri::command]
+pub fn clear_process_quarantine(
+    app: AppHandle,
+    state: tauri::State<ProcessManager>,
+    id: String,
+) -> Result<(), String> {
+    {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+
+        if !manager.configs.contains_key(&id) {
+            return Err("Process not found".to_string());
+        }
+
+        if let Some(runtime) = manager.runtime.get_mut(&id) {
+            runtime.quarantine = None;
+            runtime.recent_exits.clear();
+            runtime.reset_restart();
+        }
+    }
+
+    emit_process_event(&app, &id, ProcessEvent::QuarantineCleared);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_exits: u32, window_secs: u64) -> ProcessConfig {
+        ProcessConfig {
+            id: "p".into(),
+            crash_loop_max_exits: max_exits,
+            crash_loop_window_secs: window_secs,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn quarantines_after_more_than_max_exits() {
+        let config = config(3, 60);
+        let mut runtime = ProcessRuntime {
+            next_restart_at: Some(1),
+            ..Default::default()
+        };
+        for now in [100, 110, 120] {
+            assert!(record_exit(&mut runtime, &config, now).is_none());
+        }
+        assert!(runtime.quarantine.is_none());
+
+        let event = record_exit(&mut runtime, &config, 130).unwrap();
+        assert_eq!(event.id, "p");
+        assert_eq!(event.exits, 4);
+        assert_eq!(event.window_secs, 60);
+        assert_eq!(runtime.quarantine.as_ref().unwrap().since, 130);
+        assert!(runtime.next_restart_at.is_none());
+    }
+
+    #[test]
+    fn exits_outside_window_are_pruned() {
+        let config = config(2, 60);
+        let mut runtime = ProcessRuntime::default();
+        assert!(record_exit(&mut runtime, &config, 0).is_none());
+        assert!(record_exit(&mut runtime, &config, 50).is_none());
+        // 0 已超出窗口 [40, 100]
+        assert!(record_exit(&mut runtime, &config, 100).is_none());
+        assert_eq!(runtime.recent_exits, [50, 100]);
+        assert!(record_exit(&mut runtime, &config, 150).is_none());
+        assert_eq!(runtime.recent_exits, [100, 150]);
+        // 窗口边界上的退出仍然计入
+        assert!(record_exit(&mut runtime, &config, 160).is_some());
+        assert_eq!(runtime.recent_exits, [100, 150, 160]);
+    }
+
+    #[test]
+    fn disabled_when_max_exits_is_zero() {
+        let config = config(0, 60);
+        let mut runtime = ProcessRuntime::default();
+        for now in 0..100 {
+            assert!(record_exit(&mut runtime, &config, now).is_none());
+        }
+        assert!(runtime.recent_exits.is_empty());
+        assert!(runtime.quarantine.is_none());
+    }
+}
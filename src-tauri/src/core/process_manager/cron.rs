@@ -0,0 +1,333 @@
+//! Cron 表达式解析
+//!
+//! 支持标准的 5 段格式（分 时 日 月 周），每段可使用 `*`、列表 `a,b`、
+//! 范围 `a-b` 和步长 `*/n`、`a-b/n`，月份和星期可使用英文缩写；
+//! 另支持 `@hourly`、`@daily`、`@weekly`、`@monthly`、`@yearly` 等简写。
+//! 时间按本地时区计算
+
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike};
+
+/// 查找下一次触发时间时最多检查的步数（约覆盖 5 年）
+const MAX_SEARCH_STEPS: usize = 100_000;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// 解析后的 cron 表达式
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// 日和星期都有限制时，满足其一即可（与 cron 一致）
+    day_or: bool,
+}
+
+impl CronSchedule {
+    /// 解析 cron 表达式
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = expr.trim();
+        let expanded = match expr.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *".to_string(),
+            "@monthly" => "0 0 1 * *".to_string(),
+            "@weekly" => "0 0 * * 0".to_string(),
+            "@daily" | "@midnight" => "0 0 * * *".to_string(),
+            "@hourly" => "0 * * * *".to_string(),
+            other if other.starts_with('@') => {
+                return Err(format!("Unknown cron shorthand: {}", expr));
+            }
+            _ => expr.to_string(),
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!(
+                "Cron expression must have 5 fields (minute hour day month weekday): {}",
+                expr
+            ));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, &[])?;
+        let hours = parse_field(fields[1], 0, 23, &[])?;
+        let days_of_month = parse_field(fields[2], 1, 31, &[])?;
+        let months = parse_field(fields[3], 1, 12, &MONTH_NAMES)?;
+        let mut days_of_week = parse_field(fields[4], 0, 7, &WEEKDAY_NAMES)?;
+        // 7 和 0 都表示星期日
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        let schedule = CronSchedule {
+            minutes,
+            hours,
+            days_of_month,
+            months,
+            days_of_week,
+            day_or: !fields[2].starts_with('*') && !fields[4].starts_with('*'),
+        };
+        // 拒绝永远不会触发的表达式（如 2 月 30 日）
+        if schedule.next_after(Local::now().timestamp()).is_none() {
+            return Err(format!("Cron expression never fires: {}", expr));
+        }
+        Ok(schedule)
+    }
+
+    /// 指定的本地时间（精确到分钟）是否匹配
+    pub fn matches(&self, time: &DateTime<Local>) -> bool {
+        self.matches_day(time) && bit(self.hours, time.hour()) && bit(self.minutes, time.minute())
+    }
+
+    /// 严格晚于 `after`（秒级时间戳）的下一次触发时间，返回整分钟的时间戳
+    pub fn next_after(&self, after: i64) -> Option<i64> {
+        let mut ts = after - after.rem_euclid(60) + 60;
+        for _ in 0..MAX_SEARCH_STEPS {
+            let time = Local.timestamp_opt(ts, 0).single()?;
+            let minutes_to_skip = if !self.matches_day(&time) {
+                // 跳到下一天 00:00
+                (23 - time.hour() as i64) * 60 + (60 - time.minute() as i64)
+            } else if !bit(self.hours, time.hour()) {
+                // 跳到下一个整点
+                60 - time.minute() as i64
+            } else if !bit(self.minutes, time.minute()) {
+                1
+            } else {
+                return Some(ts);
+            };
+            ts += minutes_to_skip * 60;
+        }
+        None
+    }
+
+    fn matches_day(&self, time: &DateTime<Local>) -> bool {
+        if !bit(self.months, time.month()) {
+            return false;
+        }
+        let dom = bit(self.days_of_month, time.day());
+        let dow = bit(self.days_of_week, time.weekday().num_days_from_sunday());
+        if self.day_or {
+            dom || dow
+        } else {
+            dom && dow
+        }
+    }
+}
+
+fn bit(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// 解析单个字段，返回取值集合的位图
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid cron step: {}", part))?;
+                if step == 0 {
+                    return Err(format!("Invalid cron step: {}", part));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, names)?,
+                parse_value(end, min, max, names)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, names)?;
+            // `a/n` 表示从 a 开始到最大值，每 n 个取一个
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("Invalid cron range: {}", part));
+        }
+
+        let mut value = start;
+        while value <= end {
+            set |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(set)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let lower = value.to_ascii_lowercase();
+    // 名称从字段最小值开始编号（月份从 1 开始，星期从 0 开始）
+    let parsed = match names.iter().position(|name| *name == lower) {
+        Some(index) => index as u32 + min,
+        None => value
+            .parse()
+            .map_err(|_| format!("Invalid cron value: {}", value))?,
+    };
+    if parsed < min || parsed > max {
+        return Err(format!(
+            "Cron value {} out of range {}-{}",
+            parsed, min, max
+        ));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 本地时间的秒级时间戳
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        Local
+            .with_ymd_and_hms(y, mo, d, h, mi, 0)
+            .single()
+            .unwrap()
+            .timestamp()
+    }
+
+    fn bits(values: &[u32]) -> u64 {
+        values.iter().fold(0, |set, value| set | 1 << value)
+    }
+
+    #[test]
+    fn parses_steps_and_ranges() {
+        assert_eq!(parse_field("*/15", 0, 59, &[]), Ok(bits(&[0, 15, 30, 45])));
+        assert_eq!(parse_field("1-10/3", 0, 59, &[]), Ok(bits(&[1, 4, 7, 10])));
+        assert_eq!(parse_field("5/20", 0, 59, &[]), Ok(bits(&[5, 25, 45])));
+        assert_eq!(parse_field("1,3,8-9", 0, 23, &[]), Ok(bits(&[1, 3, 8, 9])));
+    }
+
+    #[test]
+    fn parses_month_and_weekday_names() {
+        assert_eq!(
+            parse_field("jan,Jun-aug", 1, 12, &MONTH_NAMES),
+            Ok(bits(&[1, 6, 7, 8]))
+        );
+        assert_eq!(
+            parse_field("mon-fri", 0, 7, &WEEKDAY_NAMES),
+            Ok(bits(&[1, 2, 3, 4, 5]))
+        );
+        // 7 和 0 都表示星期日
+        let sunday = CronSchedule::parse("0 0 * * 7").unwrap();
+        assert_eq!(sunday, CronSchedule::parse("0 0 * * sun").unwrap());
+        assert_eq!(sunday.days_of_week, bits(&[0]));
+    }
+
+    #[test]
+    fn expands_shorthands() {
+        assert_eq!(
+            CronSchedule::parse("@daily"),
+            CronSchedule::parse("0 0 * * *")
+        );
+        assert_eq!(
+            CronSchedule::parse("@HOURLY"),
+            CronSchedule::parse("0 * * * *")
+        );
+        assert!(CronSchedule::parse("@often").is_err());
+    }
+
+    #[test]
+    fn matches_day_of_month_or_day_of_week() {
+        // 每月 13 日或每个星期五，2026-10-01 为星期四
+        let cron = CronSchedule::parse("0 0 13 * fri").unwrap();
+        let mut at = local(2026, 10, 1, 0, 0);
+        let mut fired = Vec::new();
+        for _ in 0..4 {
+            at = cron.next_after(at).unwrap();
+            fired.push(Local.timestamp_opt(at, 0).unwrap().day());
+        }
+        assert_eq!(fired, [2, 9, 13, 16]);
+    }
+
+    #[test]
+    fn restricts_both_days_when_one_is_wildcard() {
+        // 日为 * 时只按星期匹配
+        let cron = CronSchedule::parse("0 0 * * fri").unwrap();
+        let next = cron.next_after(local(2026, 10, 1, 0, 0)).unwrap();
+        assert_eq!(next, local(2026, 10, 2, 0, 0));
+        let next = cron.next_after(next).unwrap();
+        assert_eq!(next, local(2026, 10, 9, 0, 0));
+    }
+
+    #[test]
+    fn rolls_over_days_months_and_years() {
+        let cron = CronSchedule::parse("30 23 * * *").unwrap();
+        assert_eq!(
+            cron.next_after(local(2026, 10, 17, 23, 45)),
+            Some(local(2026, 10, 18, 23, 30))
+        );
+        let cron = CronSchedule::parse("0 0 * * *").unwrap();
+        assert_eq!(
+            cron.next_after(local(2026, 10, 31, 23, 59)),
+            Some(local(2026, 11, 1, 0, 0))
+        );
+        let cron = CronSchedule::parse("@yearly").unwrap();
+        assert_eq!(
+            cron.next_after(local(2026, 1, 1, 0, 0)),
+            Some(local(2027, 1, 1, 0, 0))
+        );
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        let cron = CronSchedule::parse("*/5 * * * *").unwrap();
+        let at = local(2026, 10, 17, 12, 5);
+        assert_eq!(cron.next_after(at), Some(local(2026, 10, 17, 12, 10)));
+        assert_eq!(cron.next_after(at + 30), Some(local(2026, 10, 17, 12, 10)));
+    }
+
+    #[test]
+    fn finds_next_leap_day() {
+        let cron = CronSchedule::parse("0 0 29 2 *").unwrap();
+        assert_eq!(
+            cron.next_after(local(2025, 3, 1, 0, 0)),
+            Some(local(2028, 2, 29, 0, 0))
+        );
+    }
+
+    #[test]
+    fn never_firing_expression_gives_up() {
+        assert!(CronSchedule::parse("0 0 30 2 *").is_err());
+        assert!(CronSchedule::parse("0 0 31 4,6,9,11 *").is_err());
+        // 直接构造（parse 会拒绝），next_after 在 MAX_SEARCH_STEPS 步内返回 None
+        let cron = CronSchedule {
+            minutes: bits(&[0]),
+            hours: bits(&[0]),
+            days_of_month: bits(&[30]),
+            months: bits(&[2]),
+            days_of_week: parse_field("*", 0, 7, &[]).unwrap(),
+            day_or: false,
+        };
+        assert_eq!(cron.next_after(local(2026, 1, 1, 0, 0)), None);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in [
+            "60 * * * *",
+            "* 24 * * *",
+            "0 0 0 * *",
+            "0 0 32 * *",
+            "0 0 * 13 *",
+            "0 0 * * 8",
+            "0 0 * foo *",
+            "*/0 * * * *",
+            "*/x * * * *",
+            "1-5/ * * * *",
+            "10-5 * * * *",
+            "0 0 * *",
+            "0 0 * * * *",
+            "",
+        ] {
+            assert!(CronSchedule::parse(expr).is_err(), "{}", expr);
+        }
+    }
+}
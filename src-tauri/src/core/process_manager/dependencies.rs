@@ -0,0 +1,290 @@
+//! 进程依赖关系
+//!
+//! 进程可通过 `depends_on` 声明依赖的其他进程：启动时先启动依赖，
+//! 停止时先停止依赖它的进程。配置时拒绝不存在的依赖和循环依赖
+
+use std::collections::{HashMap, HashSet};
+
+use super::types::ProcessConfig;
+
+/// 校验进程的依赖：依赖的进程必须存在，且不能依赖自身或形成循环
+///
+/// `configs` 为当前所有进程配置，`config` 为新增或修改后的配置
+pub(super) fn validate_dependencies(
+    configs: &HashMap<String, ProcessConfig>,
+    config: &ProcessConfig,
+) -> Result<(), String> {
+    for dep in &config.depends_on {
+        if *dep == config.id {
+            return Err("A process cannot depend on itself".to_string());
+        }
+        if !configs.contains_key(dep) {
+            return Err(format!("Dependency not found: {}", dep));
+        }
+    }
+
+    let deps_of = |id: &str| -> Vec<String> {
+        if id == config.id {
+            config.depends_on.clone()
+        } else {
+            configs
+                .get(id)
+                .map(|c| c.depends_on.clone())
+                .unwrap_or_default()
+        }
+    };
+    topo_sort(std::slice::from_ref(&config.id), &deps_of).map_err(|cycle| {
+        let names: Vec<String> = cycle
+            .iter()
+            .map(|id| {
+                if *id == config.id {
+                    config.name.clone()
+                } else {
+                    configs
+                        .get(id)
+                        .map(|c| c.name.clone())
+                        .unwrap_or(id.clone())
+                }
+            })
+            .collect();
+        format!("Dependency cycle detected: {}", names.join(" -> "))
+    })?;
+    Ok(())
+}
+
+/// 按启动顺序排列进程及其所有（间接）依赖，依赖排在依赖它的进程之前
+///
+/// 没有依赖关系的进程保持 `ids` 中的先后顺序
+pub(super) fn start_order(
+    configs: &HashMap<String, ProcessConfig>,
+    ids: &[String],
+) -> Result<Vec<String>, String> {
+    let deps_of = |id: &str| -> Vec<String> {
+        configs
+            .get(id)
+            .map(|c| {
+                c.depends_on
+                    .iter()
+                    .filter(|dep| configs.contains_key(*dep))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    topo_sort(ids, &deps_of)
+        .map_err(|cycle| format!("Dependency cycle detected: {}", cycle.join(" -> ")))
+}
+
+/// 直接或间接依赖指定进程的所有进程
+pub(super) fn dependents(configs: &HashMap<String, ProcessConfig>, id: &str) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    let mut queue: Vec<&str> = vec![id];
+    while let Some(current) = queue.pop() {
+        for config in configs.values() {
+            if config.depends_on.iter().any(|dep| dep == current)
+                && config.id != id
+                && !result.contains(&config.id)
+            {
+                result.push(config.id.clone());
+                queue.push(&config.id);
+            }
+        }
+    }
+    result
+}
+
+/// 按停止顺序将进程分层，同一层的进程可以并行停止
+///
+/// 依赖某进程的进程（包括经由不在 `ids` 中的进程间接依赖）总是在更靠前的层中
+pub(super) fn shutdown_layers(
+    configs: &HashMap<String, ProcessConfig>,
+    ids: &[String],
+) -> Vec<Vec<String>> {
+    let members: HashSet<&String> = ids.iter().collect();
+    let mut depths: HashMap<String, usize> = HashMap::new();
+    for id in ids {
+        shutdown_depth(configs, &members, id, &mut depths, &mut HashSet::new());
+    }
+
+    let mut layers: Vec<Vec<String>> = Vec::new();
+    for id in ids {
+        let depth = depths.get(id).copied().unwrap_or(0);
+        if layers.len() <= depth {
+            layers.resize(depth + 1, Vec::new());
+        }
+        layers[depth].push(id.clone());
+    }
+    layers
+}
+
+/// 计算进程所在的停止层：没有（待停止的）进程依赖它时为 0，否则比依赖它的进程大 1
+fn shutdown_depth(
+    configs: &HashMap<String, ProcessConfig>,
+    members: &HashSet<&String>,
+    id: &str,
+    depths: &mut HashMap<String, usize>,
+    visiting: &mut HashSet<String>,
+) -> usize {
+    if let Some(depth) = depths.get(id) {
+        return *depth;
+    }
+    // 已存在的循环依赖不应出现，出现时忽略该依赖边
+    if !visiting.insert(id.to_string()) {
+        return 0;
+    }
+    let mut depth = 0;
+    for dependent in dependents(configs, id) {
+        if members.contains(&dependent) {
+            let d = shutdown_depth(configs, members, &dependent, depths, visiting);
+            depth = depth.max(d + 1);
+        }
+    }
+    visiting.remove(id);
+    depths.insert(id.to_string(), depth);
+    depth
+}
+
+/// 深度优先拓扑排序，依赖排在前面；存在循环时返回构成循环的路径
+fn topo_sort(
+    roots: &[String],
+    deps_of: &dyn Fn(&str) -> Vec<String>,
+) -> Result<Vec<String>, Vec<String>> {
+    let mut order: Vec<String> = Vec::new();
+    let mut done: HashSet<String> = HashSet::new();
+    let mut path: Vec<String> = Vec::new();
+    for root in roots {
+        visit(root, deps_of, &mut order, &mut done, &mut path)?;
+    }
+    Ok(order)
+}
+
+fn visit(
+    id: &str,
+    deps_of: &dyn Fn(&str) -> Vec<String>,
+    order: &mut Vec<String>,
+    done: &mut HashSet<String>,
+    path: &mut Vec<String>,
+) -> Result<(), Vec<String>> {
+    if done.contains(id) {
+        return Ok(());
+    }
+    if let Some(pos) = path.iter().position(|p| p == id) {
+        let mut cycle = path[pos..].to_vec();
+        cycle.push(id.to_string());
+        return Err(cycle);
+    }
+    path.push(id.to_string());
+    for dep in deps_of(id) {
+        visit(&dep, deps_of, order, done, path)?;
+    }
+    path.pop();
+    done.insert(id.to_string());
+    order.push(id.to_string());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(id: &str, depends_on: &[&str]) -> ProcessConfig {
+        ProcessConfig {
+            id: id.into(),
+            name: id.to_uppercase(),
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn configs(list: &[(&str, &[&str])]) -> HashMap<String, ProcessConfig> {
+        list.iter()
+            .map(|(id, deps)| (id.to_string(), config(id, deps)))
+            .collect()
+    }
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn rejects_self_missing_and_cyclic_dependencies() {
+        let all = configs(&[("db", &[]), ("api", &["db"]), ("web", &["api"])]);
+        assert!(validate_dependencies(&all, &config("worker", &["db", "api"])).is_ok());
+        assert_eq!(
+            validate_dependencies(&all, &config("worker", &["worker"])).unwrap_err(),
+            "A process cannot depend on itself"
+        );
+        assert_eq!(
+            validate_dependencies(&all, &config("worker", &["cache"])).unwrap_err(),
+            "Dependency not found: cache"
+        );
+        // 修改 db 使其依赖 web：db -> web -> api -> db
+        assert_eq!(
+            validate_dependencies(&all, &config("db", &["web"])).unwrap_err(),
+            "Dependency cycle detected: DB -> WEB -> API -> DB"
+        );
+    }
+
+    #[test]
+    fn start_order_puts_dependencies_first() {
+        let all = configs(&[
+            ("db", &[]),
+            ("cache", &[]),
+            ("api", &["db", "cache"]),
+            ("web", &["api"]),
+            ("docs", &[]),
+        ]);
+        let order = start_order(&all, &ids(&["web", "docs", "db"])).unwrap();
+        assert_eq!(order, ids(&["db", "cache", "api", "web", "docs"]));
+        // 不存在的依赖被忽略
+        let all = configs(&[("api", &["gone"])]);
+        assert_eq!(start_order(&all, &ids(&["api"])).unwrap(), ids(&["api"]));
+    }
+
+    #[test]
+    fn start_order_rejects_cycles() {
+        let all = configs(&[("a", &["b"]), ("b", &["a"])]);
+        assert_eq!(
+            start_order(&all, &ids(&["a"])).unwrap_err(),
+            "Dependency cycle detected: a -> b -> a"
+        );
+    }
+
+    #[test]
+    fn dependents_are_transitive() {
+        let all = configs(&[
+            ("db", &[]),
+            ("api", &["db"]),
+            ("web", &["api"]),
+            ("docs", &[]),
+        ]);
+        let mut result = dependents(&all, "db");
+        result.sort();
+        assert_eq!(result, ids(&["api", "web"]));
+        assert!(dependents(&all, "web").is_empty());
+    }
+
+    #[test]
+    fn shutdown_layers_stop_dependents_first() {
+        let all = configs(&[
+            ("db", &[]),
+            ("api", &["db"]),
+            ("worker", &["db"]),
+            ("web", &["api"]),
+            ("docs", &[]),
+        ]);
+        let layers = shutdown_layers(&all, &ids(&["db", "api", "worker", "web", "docs"]));
+        assert_eq!(
+            layers,
+            vec![ids(&["worker", "web", "docs"]), ids(&["api"]), ids(&["db"])]
+        );
+    }
+
+    #[test]
+    fn shutdown_layers_through_non_member_dependents() {
+        // api 不在停止列表中，但 web 经由 api 间接依赖 db，仍需先于 db 停止
+        let all = configs(&[("db", &[]), ("api", &["db"]), ("web", &["api"])]);
+        let layers = shutdown_layers(&all, &ids(&["db", "web"]));
+        assert_eq!(layers, vec![ids(&["web"]), ids(&["db"])]);
+    }
+}
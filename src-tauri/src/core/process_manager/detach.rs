@@ -0,0 +1,179 @@
+//! 脱离模式
+//!
+//! 设置了 survive_app_exit 的进程（开启全局设置时为所有进程）以脱离模式启动：
+//! 在独立会话中运行，输出写入日志文件而不是管道，退出应用时保持运行，
+//! 下次启动时重新接管。输出通过跟踪日志文件读取，重新接管后也能继续显示
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::runner::push_output_line;
+use super::shutdown::shutdown_processes;
+use super::state::{ProcessManager, MAX_TOTAL_OUTPUT_BYTES};
+use super::types::{ProcessConfig, ShutdownProgress};
+use crate::paths::AppPaths;
+use crate::storage::{load_survive_app_exit, DbState};
+
+/// 跟踪日志文件时的轮询间隔
+const LOG_POLL_INTERVAL_MS: u64 = 200;
+
+/// 脱离模式进程的输出日志文件
+pub(crate) struct LogFiles {
+    pub stdout: PathBuf,
+    pub stderr: PathBuf,
+}
+
+impl LogFiles {
+    /// 进程的日志文件：`{app_data_dir}/Logs/processes/<id>.stdout.log` 和 `<id>.stderr.log`
+    pub fn for_process(app: &AppHandle, id: &str) -> Result<Self, String> {
+        let dir = AppPaths::new(app)?.logs_dir().join("processes");
+        Ok(Self {
+            stdout: dir.join(format!("{}.stdout.log", id)),
+            stderr: dir.join(format!("{}.stderr.log", id)),
+        })
+    }
+
+    /// 重新创建日志文件，返回子进程的 stdout 和 stderr
+    ///
+    /// 先删除旧文件，仍在跟踪旧文件的线程不会读到新实例的输出
+    pub fn create(&self) -> Result<(File, File), String> {
+        if let Some(dir) = self.stdout.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+        }
+        let create = |path: &Path| {
+            let _ = std::fs::remove_file(path);
+            File::create(path)
+                .map_err(|e| format!("Failed to create log file {}: {}", path.display(), e))
+        };
+        Ok((create(&self.stdout)?, create(&self.stderr)?))
+    }
+}
+
+/// 进程是否以脱离模式启动：进程设置了 survive_app_exit 或开启了全局设置
+pub(super) fn runs_detached(app: &AppHandle, config: &ProcessConfig) -> bool {
+    config.survive_app_exit
+        || app
+            .try_state::<DbState>()
+            .and_then(|db| db.0.lock().ok().map(|conn| load_survive_app_exit(&conn)))
+            .unwrap_or(false)
+}
+
+/// 启动进程时使用的日志文件，不以脱离模式启动时返回 None
+pub(super) fn detached_log_files(
+    app: &AppHandle,
+    id: &str,
+    config: &ProcessConfig,
+) -> Result<Option<LogFiles>, String> {
+    if !runs_detached(app, config) {
+        return Ok(None);
+    }
+    LogFiles::for_process(app, id).map(Some)
+}
+
+/// 跟踪脱离模式进程的日志文件，将新的输出记录到输出缓冲，该运行实例退出后停止
+pub(super) fn follow_log_files(app: &AppHandle, manager: &ProcessManager, id: &str, pid: u32) {
+    let files = match LogFiles::for_process(app, id) {
+        Ok(files) => files,
+        Err(e) => {
+            eprintln!("Failed to follow output of process {}: {}", id, e);
+            return;
+        }
+    };
+    for (path, output_type) in [(files.stdout, "stdout"), (files.stderr, "stderr")] {
+        let app = app.clone();
+        let manager = manager.clone();
+        let id = id.to_string();
+        thread::spawn(move || follow_log(&app, &manager, &id, pid, &path, output_type));
+    }
+}
+
+/// 跟踪单个日志文件
+///
+/// 只读取文件末尾最多 `MAX_TOTAL_OUTPUT_BYTES` 的内容，重新接管时可显示之前的部分输出
+fn follow_log(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    path: &Path,
+    output_type: &str,
+) {
+    let Ok(mut file) = File::open(path) else {
+        return;
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(MAX_TOTAL_OUTPUT_BYTES as u64);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return;
+    }
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    if start > 0 {
+        // 跳过不完整的第一行
+        let _ = reader.read_until(b'\n', &mut line);
+        line.clear();
+    }
+
+    let push = |line: &[u8]| {
+        let text = String::from_utf8_lossy(line);
+        let text = text.trim_end_matches(['\r', '\n']);
+        push_output_line(app, manager, id, output_type, text);
+    };
+    loop {
+        // 先确认运行状态再读取，保证进程退出前写入的输出都能读到
+        let running = manager
+            .lock()
+            .map(|state| state.processes.get(id).is_some_and(|r| r.child.id() == pid))
+            .unwrap_or(false);
+        // 读取已写入的所有完整行，不完整的行等待剩余部分写入
+        while let Ok(n) = reader.read_until(b'\n', &mut line) {
+            if n == 0 || !line.ends_with(b"\n") {
+                break;
+            }
+            push(&line);
+            line.clear();
+        }
+        if !running {
+            if !line.is_empty() {
+                push(&line);
+            }
+            return;
+        }
+        thread::sleep(Duration::from_millis(LOG_POLL_INTERVAL_MS));
+    }
+}
+
+/// 应用退出时停止进程
+///
+/// 以脱离模式运行的进程保持运行（保留其实例记录，下次启动时重新接管），其余进程全部停止，
+/// 停止进度通过 `on_progress` 报告
+pub fn stop_processes_on_exit(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    on_progress: impl Fn(&ShutdownProgress),
+) {
+    if let Ok(mut state) = manager.lock() {
+        state.begin_shutdown();
+        let detached: Vec<String> = state
+            .processes
+            .iter()
+            .filter(|(_, running)| running.detached)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in detached {
+            if let Some(running) = state.processes.remove(&id) {
+                println!(
+                    "Leaving process {} running after exit (PID {})",
+                    id,
+                    running.child.id()
+                );
+            }
+        }
+    }
+    shutdown_processes(app, manager, on_progress);
+}
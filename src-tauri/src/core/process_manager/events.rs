@@ -0,0 +1,97 @@
+//! 进程生命周期事件
+//!
+//! 所有生命周期事件都经由 `emit_process_event` 统一发送：
+//! 前端通过 `process-event` 事件接收，Rust 内部模块（托盘、通知、历史记录等）
+//! 通过 `subscribe_process_events` 订阅
+
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::types::{
+    ProcessEvent, ProcessEventMessage, ProcessExitedEvent, ProcessQuarantinedEvent,
+};
+use super::utils::current_timestamp_millis;
+
+/// 进程事件处理函数
+pub type ProcessEventHandler = Arc<dyn Fn(&ProcessEventMessage) + Send + Sync>;
+
+/// 进程事件订阅者（作为 Tauri 托管状态）
+#[derive(Default)]
+pub struct ProcessEventBus {
+    handlers: Mutex<Vec<ProcessEventHandler>>,
+}
+
+impl ProcessEventBus {
+    /// 添加订阅者
+    pub fn subscribe<F>(&self, handler: F)
+    where
+        F: Fn(&ProcessEventMessage) + Send + Sync + 'static,
+    {
+        if let Ok(mut handlers) = self.handlers.lock() {
+            handlers.push(Arc::new(handler));
+        }
+    }
+
+    fn handlers(&self) -> Vec<ProcessEventHandler> {
+        self.handlers
+            .lock()
+            .map(|handlers| handlers.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// 订阅进程生命周期事件
+///
+/// 处理函数在发送事件的线程中同步调用（此时未持有进程管理器的锁），不应长时间阻塞
+pub fn subscribe_process_events<F>(app: &AppHandle, handler: F)
+where
+    F: Fn(&ProcessEventMessage) + Send + Sync + 'static,
+{
+    if app.try_state::<ProcessEventBus>().is_none() {
+        app.manage(ProcessEventBus::default());
+    }
+    app.state::<ProcessEventBus>().subscribe(handler);
+}
+
+/// 发送进程生命周期事件（应在释放状态锁后调用）
+pub(super) fn emit_process_event(app: &AppHandle, id: &str, event: ProcessEvent) {
+    let message = ProcessEventMessage {
+        id: id.to_string(),
+        timestamp: current_timestamp_millis(),
+        event,
+    };
+
+    if let Some(bus) = app.try_state::<ProcessEventBus>() {
+        for handler in bus.handlers() {
+            handler(&message);
+        }
+    }
+
+    // 退出与隔离事件同时以专用事件名发送
+    match &message.event {
+        ProcessEvent::Exited { pid, exit } => {
+            let exited = ProcessExitedEvent {
+                id: message.id.clone(),
+                pid: *pid,
+                exit: exit.clone(),
+            };
+            let _ = app.emit("process-exited", exited);
+        }
+        ProcessEvent::Quarantined {
+            reason,
+            exits,
+            window_secs,
+        } => {
+            let quarantined = ProcessQuarantinedEvent {
+                id: message.id.clone(),
+                reason: reason.clone(),
+                exits: *exits,
+                window_secs: *window_secs,
+            };
+            let _ = app.emit("process-quarantined", quarantined);
+        }
+        _ => {}
+    }
+
+    let _ = app.emit("process-event", message);
+}
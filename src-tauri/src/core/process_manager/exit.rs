@@ -0,0 +1,97 @@
+//! 进程退出记录
+//!
+//! 记录每个进程最近一次退出的退出码/信号、退出时间、运行时长和原因，
+//! 持久化到数据库（同时保留最近几次运行的记录）并发送退出事件
+
+use std::process::ExitStatus;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+use super::events::emit_process_event;
+use super::limits::Cgroup;
+use super::stale::release_pid_file;
+use super::state::ProcessManagerState;
+use super::supervisor::is_success_exit;
+use super::types::{ExitInfo, ExitReason, ProcessConfig, ProcessEvent, ProcessExitedEvent};
+use super::utils::current_timestamp;
+use crate::storage::{delete_process_instance, save_process_exit, save_process_run, DbState};
+
+/// 根据退出状态构造退出信息，`launched_at` 为该运行实例的启动时刻
+pub(super) fn exit_info(
+    status: Option<&ExitStatus>,
+    reason: ExitReason,
+    launched_at: Instant,
+) -> ExitInfo {
+    #[cfg(unix)]
+    let signal = {
+        use std::os::unix::process::ExitStatusExt;
+        status.and_then(|s| s.signal())
+    };
+    #[cfg(not(unix))]
+    let signal = None;
+
+    ExitInfo {
+        code: status.and_then(|s| s.code()),
+        signal,
+        exited_at: current_timestamp(),
+        reason,
+        duration_ms: Some(launched_at.elapsed().as_millis() as u64),
+    }
+}
+
+/// 判断进程自行退出的原因：正常退出、崩溃或被所在 cgroup 的 OOM 结束
+pub(super) fn classify_exit(
+    config: &ProcessConfig,
+    status: Option<&ExitStatus>,
+    cgroup: Option<&Cgroup>,
+) -> ExitReason {
+    if cgroup.is_some_and(Cgroup::oom_killed) {
+        ExitReason::OomKilled
+    } else if is_success_exit(config, status) {
+        ExitReason::Exited
+    } else {
+        ExitReason::Crashed
+    }
+}
+
+/// 在内存中记录进程退出信息（调用方持有状态锁）
+pub(super) fn record_exit(state: &mut ProcessManagerState, id: &str, info: &ExitInfo) {
+    println!(
+        "Process {} exited: {} (code {:?}, signal {:?})",
+        id,
+        info.reason.as_str(),
+        info.code,
+        info.signal
+    );
+    state.runtime.entry(id.to_string()).or_default().last_exit = Some(info.clone());
+}
+
+/// 将退出信息写入数据库并发送退出事件（应在释放状态锁后调用）
+pub(super) fn publish_exits(app: &AppHandle, exits: &[ProcessExitedEvent]) {
+    if exits.is_empty() {
+        return;
+    }
+    if let Some(db) = app.try_state::<DbState>() {
+        if let Ok(conn) = db.0.lock() {
+            for event in exits {
+                if let Err(e) = save_process_exit(&conn, &event.id, &event.exit) {
+                    eprintln!("Failed to save exit info for process {}: {}", event.id, e);
+                }
+                if let Err(e) = save_process_run(&conn, &event.id, &event.exit) {
+                    eprintln!("Failed to save run for process {}: {}", event.id, e);
+                }
+                if let Err(e) = delete_process_instance(&conn, &event.id, event.pid) {
+                    eprintln!("Failed to delete instance of process {}: {}", event.id, e);
+                }
+            }
+        }
+    }
+    for event in exits {
+        release_pid_file(app, &event.id, event.pid);
+        let exited = ProcessEvent::Exited {
+            pid: event.pid,
+            exit: event.exit.clone(),
+        };
+        emit_process_event(app, &event.id, exited);
+    }
+}
@@ -0,0 +1,171 @@
+//! 托管进程句柄
+//!
+//! 运行中的进程可能是本实例启动的子进程，也可能是应用重启后重新接管（adopted）的进程。
+//! 接管的进程不是本实例的子进程，无法获取其退出码，只能通过 PID 和进程启动时间
+//! 判断是否仍在运行（退出状态未知时按异常退出处理）
+
+use std::process::{Child, ExitStatus};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 强制结束接管的进程后等待其退出的最长时间
+const ADOPTED_KILL_WAIT_MS: u64 = 5000;
+/// 等待接管的进程退出时的轮询间隔
+const ADOPTED_POLL_INTERVAL_MS: u64 = 50;
+
+/// 运行中进程的句柄
+pub(crate) enum ProcessHandle {
+    /// 本实例启动的子进程
+    Spawned(Child),
+    /// 应用重启后重新接管的进程，`start_time` 用于确认 PID 未被复用
+    Adopted { pid: u32, start_time: u64 },
+}
+
+/// 进程退出检查结果
+pub(crate) enum ExitPoll {
+    Running,
+    /// 已退出，接管的进程无法获取退出状态
+    Exited(Option<ExitStatus>),
+}
+
+impl ProcessHandle {
+    pub fn id(&self) -> u32 {
+        match self {
+            ProcessHandle::Spawned(child) => child.id(),
+            ProcessHandle::Adopted { pid, .. } => *pid,
+        }
+    }
+
+    /// 是否为重新接管的进程
+    pub fn is_adopted(&self) -> bool {
+        matches!(self, ProcessHandle::Adopted { .. })
+    }
+
+    /// 检查进程是否已退出（不阻塞）
+    pub fn try_wait(&mut self) -> std::io::Result<ExitPoll> {
+        match self {
+            ProcessHandle::Spawned(child) => Ok(match child.try_wait()? {
+                Some(status) => ExitPoll::Exited(Some(status)),
+                None => ExitPoll::Running,
+            }),
+            ProcessHandle::Adopted { pid, start_time } => {
+                Ok(if is_same_process(*pid, *start_time) {
+                    ExitPoll::Running
+                } else {
+                    ExitPoll::Exited(None)
+                })
+            }
+        }
+    }
+
+    /// 强制结束进程
+    pub fn kill(&mut self) -> std::io::Result<()> {
+        match self {
+            ProcessHandle::Spawned(child) => child.kill(),
+            ProcessHandle::Adopted { pid, start_time } => {
+                if is_same_process(*pid, *start_time) {
+                    kill_pid(*pid)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// 等待进程退出，返回退出状态（接管的进程返回 None）
+    pub fn wait(&mut self) -> Option<ExitStatus> {
+        match self {
+            ProcessHandle::Spawned(child) => child.wait().ok(),
+            ProcessHandle::Adopted { pid, start_time } => {
+                let deadline = Instant::now() + Duration::from_millis(ADOPTED_KILL_WAIT_MS);
+                while is_same_process(*pid, *start_time) && Instant::now() < deadline {
+                    thread::sleep(Duration::from_millis(ADOPTED_POLL_INTERVAL_MS));
+                }
+                None
+            }
+        }
+    }
+}
+
+/// PID 对应的进程是否仍是启动时间为 `start_time` 的同一个进程
+pub(super) fn is_same_process(pid: u32, start_time: u64) -> bool {
+    process_start_time(pid) == Some(start_time)
+}
+
+/// 进程启动时间（开机后的时钟滴答数，读取 `/proc/<pid>/stat` 第 22 个字段）
+///
+/// 已退出但尚未被回收的僵尸进程视为已退出
+#[cfg(target_os = "linux")]
+pub(super) fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // 进程名可能包含空格和括号，从最后一个 ')' 之后开始解析（第 3 个字段起）
+    let mut fields = stat[stat.rfind(')')? + 1..].split_whitespace();
+    if fields.next()? == "Z" {
+        return None;
+    }
+    fields.nth(18)?.parse().ok()
+}
+
+/// 其他 Unix 平台无法可靠获取进程启动时间
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(super) fn process_start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// 进程创建时间（FILETIME），已退出的进程返回 None
+#[cfg(windows)]
+pub(super) fn process_start_time(pid: u32) -> Option<u64> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let mut exit_code = 0u32;
+        let mut creation: FILETIME = std::mem::zeroed();
+        let mut exit: FILETIME = std::mem::zeroed();
+        let mut kernel: FILETIME = std::mem::zeroed();
+        let mut user: FILETIME = std::mem::zeroed();
+        let running =
+            GetExitCodeProcess(handle, &mut exit_code) != 0 && exit_code == STILL_ACTIVE as u32;
+        let ok = GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user);
+        CloseHandle(handle);
+        if ok == 0 || !running {
+            return None;
+        }
+        Some(((creation.dwHighDateTime as u64) << 32) | creation.dwLowDateTime as u64)
+    }
+}
+
+#[cfg(unix)]
+fn kill_pid(pid: u32) -> std::io::Result<()> {
+    if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
+#[cfg(windows)]
+fn kill_pid(pid: u32) -> std::io::Result<()> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return Err(std::io::Error::last_os_error());
+        }
+        let ok = TerminateProcess(handle, 1);
+        let error = std::io::Error::last_os_error();
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(error);
+        }
+    }
+    Ok(())
+}
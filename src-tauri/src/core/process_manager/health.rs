@@ -0,0 +1,352 @@
+//! 健康检查
+//!
+//! 为配置了 `health_check` 的进程启动独立的检查线程，按间隔执行 TCP 连接、
+//! HTTP 请求或探测命令。连续失败达到阈值后标记为不健康，可选地按重启流程重启进程
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::events::emit_process_event;
+use super::process_tree::{configure_command, ProcessTree};
+use super::state::ProcessManager;
+use super::supervisor::restart_instance;
+use super::types::{
+    ExitReason, HealthCheckConfig, HealthProbe, HealthState, ProcessConfig, ProcessEvent,
+};
+
+/// 探测命令执行期间的轮询间隔
+const COMMAND_POLL_INTERVAL_MS: u64 = 50;
+
+/// 为运行中的进程启动健康检查线程
+///
+/// 线程在进程退出或被替换（PID 变化）后自动结束
+pub(super) fn start_health_check(app: &AppHandle, manager: &ProcessManager, id: &str, pid: u32) {
+    let app = app.clone();
+    let manager = manager.clone();
+    let id = id.to_string();
+    thread::spawn(move || loop {
+        let Some(check) = current_check(&manager, &id, pid) else {
+            return;
+        };
+        thread::sleep(Duration::from_millis(check.interval_ms));
+
+        // 睡眠期间配置可能被修改，使用最新的配置执行检查
+        let Some(config) = running_config(&manager, &id, pid) else {
+            return;
+        };
+        let Some(check) = config.health_check.clone() else {
+            return;
+        };
+        let result = run_probe(&check, &config);
+
+        let (changed, restart) = {
+            let Ok(mut state) = manager.lock() else {
+                return;
+            };
+            if state.processes.get(&id).is_none_or(|r| r.child.id() != pid) {
+                return;
+            }
+            let runtime = state.runtime.entry(id.clone()).or_default();
+            let previous = runtime.health;
+            match &result {
+                Ok(()) => {
+                    runtime.health_failures = 0;
+                    runtime.health = HealthState::Healthy;
+                }
+                Err(_) => {
+                    runtime.health_failures = runtime.health_failures.saturating_add(1);
+                    if runtime.health_failures >= check.failure_threshold {
+                        runtime.health = HealthState::Unhealthy;
+                    }
+                }
+            }
+            let restart =
+                check.restart_on_failure && runtime.health_failures >= check.failure_threshold;
+            let changed = (previous != runtime.health).then_some(runtime.health);
+            (changed, restart)
+        };
+
+        if let Some(health) = changed {
+            emit_process_event(
+                &app,
+                &id,
+                ProcessEvent::HealthChanged {
+                    health,
+                    message: result.as_ref().err().cloned(),
+                },
+            );
+        }
+        if let Err(e) = &result {
+            eprintln!("Health check failed for process {}: {}", id, e);
+        }
+        if restart {
+            eprintln!("Process {} is unhealthy, restarting", id);
+            restart_instance(&app, &manager, &id, pid, ExitReason::HealthCheckFailed);
+            return;
+        }
+    });
+}
+
+/// 获取当前运行实例的健康检查配置
+fn current_check(manager: &ProcessManager, id: &str, pid: u32) -> Option<HealthCheckConfig> {
+    running_config(manager, id, pid)?.health_check
+}
+
+/// 获取仍在运行的进程（PID 未变）的最新配置
+fn running_config(manager: &ProcessManager, id: &str, pid: u32) -> Option<ProcessConfig> {
+    let state = manager.lock().ok()?;
+    if state.processes.get(id).is_none_or(|r| r.child.id() != pid) {
+        return None;
+    }
+    state.configs.get(id).cloned()
+}
+
+/// 执行一次健康检查
+pub(super) fn run_probe(check: &HealthCheckConfig, config: &ProcessConfig) -> Result<(), String> {
+    let timeout = Duration::from_millis(check.timeout_ms);
+    match &check.probe {
+        HealthProbe::Tcp { host, port } => probe_tcp(host, *port, timeout),
+        HealthProbe::Http {
+            url,
+            expected_status,
+        } => probe_http(url, *expected_status, timeout),
+        HealthProbe::Command {
+            command,
+            expected_exit_code,
+        } => probe_command(command, *expected_exit_code, config, timeout),
+    }
+}
+
+/// 连接 TCP 端口
+fn connect(host: &str, port: u16, timeout: Duration) -> Result<TcpStream, String> {
+    let addrs = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("Failed to resolve {}:{}: {}", host, port, e))?;
+    let mut last_error = format!("No address for {}:{}", host, port);
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = format!("Failed to connect to {}: {}", addr, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// 检查 TCP 端口是否接受连接
+pub(super) fn probe_tcp(host: &str, port: u16, timeout: Duration) -> Result<(), String> {
+    connect(host, port, timeout).map(|_| ())
+}
+
+/// 发送 HTTP GET 请求并检查响应状态码（仅支持 http）
+fn probe_http(url: &str, expected_status: u16, timeout: Duration) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    if parsed.scheme() != "http" {
+        return Err(format!("Unsupported URL scheme: {}", parsed.scheme()));
+    }
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("URL has no host: {}", url))?;
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    let path = match parsed.query() {
+        Some(query) => format!("{}?{}", parsed.path(), query),
+        None => parsed.path().to_string(),
+    };
+
+    let deadline = Instant::now() + timeout;
+    let mut stream = connect(host, port, timeout)?;
+    let remaining = deadline
+        .saturating_duration_since(Instant::now())
+        .max(Duration::from_millis(1));
+    stream
+        .set_read_timeout(Some(remaining))
+        .and_then(|_| stream.set_write_timeout(Some(remaining)))
+        .map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: ServiceLauncher\r\nConnection: close\r\n\r\n",
+        path,
+        parsed.authority()
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    // 只需要读取状态行
+    let mut response = Vec::new();
+    let mut buf = [0u8; 512];
+    while !response.contains(&b'\n') {
+        let n = stream
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        response.extend_from_slice(&buf[..n]);
+    }
+
+    let status_line = String::from_utf8_lossy(&response);
+    let status = status_line
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| "Invalid HTTP response".to_string())?;
+    if status == expected_status {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unexpected HTTP status {} (expected {})",
+            status, expected_status
+        ))
+    }
+}
+
+/// 在进程的工作目录中通过系统 shell 执行探测命令，超时后结束命令的进程树
+fn probe_command(
+    command: &str,
+    expected_exit_code: i32,
+    config: &ProcessConfig,
+    timeout: Duration,
+) -> Result<(), String> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    cmd.current_dir(&config.working_dir)
+        .envs(&config.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    configure_command(&mut cmd, false);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run health check command: {}", e))?;
+    let tree = ProcessTree::attach(&child);
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() >= timeout => {
+                tree.kill();
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "Health check command timed out after {} ms",
+                    timeout.as_millis()
+                ));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(COMMAND_POLL_INTERVAL_MS)),
+            Err(e) => return Err(e.to_string()),
+        }
+    };
+
+    match status.code() {
+        Some(code) if code == expected_exit_code => Ok(()),
+        Some(code) => Err(format!(
+            "Health check command exited with code {} (expected {})",
+            code, expected_exit_code
+        )),
+        None => Err("Health check command was terminated by a signal".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+    use std::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_millis(2000);
+
+    /// 在回环地址上接受一个连接，读取请求头后写入固定的响应
+    fn serve_once(response: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = std::io::BufReader::new(stream);
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let _ = reader.get_mut().write_all(response.as_bytes());
+        });
+        port
+    }
+
+    #[test]
+    fn probe_tcp_connects_to_listening_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(probe_tcp("127.0.0.1", port, TIMEOUT).is_ok());
+    }
+
+    #[test]
+    fn probe_tcp_fails_on_closed_port() {
+        let port = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        assert!(probe_tcp("127.0.0.1", port, TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn probe_http_accepts_expected_status() {
+        let port = serve_once("HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n");
+        let url = format!("http://127.0.0.1:{}/health?full=1", port);
+        assert!(probe_http(&url, 204, TIMEOUT).is_ok());
+    }
+
+    #[test]
+    fn probe_http_rejects_unexpected_status() {
+        let port = serve_once("HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n");
+        let url = format!("http://127.0.0.1:{}/", port);
+        let error = probe_http(&url, 200, TIMEOUT).unwrap_err();
+        assert!(error.contains("503"), "{}", error);
+    }
+
+    #[test]
+    fn probe_http_times_out_without_response() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // 接受连接但不响应，直到测试结束
+        thread::spawn(move || {
+            let _stream = listener.accept();
+            thread::sleep(Duration::from_secs(10));
+        });
+        let start = Instant::now();
+        let url = format!("http://127.0.0.1:{}/", port);
+        assert!(probe_http(&url, 200, Duration::from_millis(200)).is_err());
+        assert!(start.elapsed() < TIMEOUT);
+    }
+
+    #[test]
+    fn probe_http_rejects_https() {
+        assert!(probe_http("https://127.0.0.1/", 200, TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn probe_command_matches_exit_code() {
+        let config = ProcessConfig {
+            working_dir: std::env::temp_dir().to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        assert!(probe_command("exit 0", 0, &config, TIMEOUT).is_ok());
+        assert!(probe_command("exit 3", 3, &config, TIMEOUT).is_ok());
+        let error = probe_command("exit 3", 0, &config, TIMEOUT).unwrap_err();
+        assert!(error.contains("code 3"), "{}", error);
+    }
+}
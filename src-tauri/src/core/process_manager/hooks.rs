@@ -0,0 +1,253 @@
+//! 生命周期钩子
+//!
+//! 进程可配置启动前（pre_start）、启动后（post_start）、停止前（pre_stop）和停止后（post_stop）
+//! 依次执行的钩子命令，例如启动前执行数据库迁移、停止后删除锁文件。
+//! 钩子命令与进程使用相同的工作目录和环境变量，输出以 `hook` 类型记录到进程输出中。
+//! 超时或非零退出码视为失败，设置为 fail 的钩子失败时中止后续钩子：
+//! 启动前的钩子失败时不启动进程，启动后的钩子失败时结束进程，手动停止时停止前的钩子失败则不停止进程
+//! （退出应用、健康检查失败等内部流程仍然停止）。进程自行退出或被内部流程结束后同样执行停止后的钩子
+
+use std::io::{BufRead, BufReader, Read};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::events::emit_process_event;
+use super::exit::publish_exits;
+use super::runner::{push_output_line, spawn_process};
+use super::state::ProcessManager;
+use super::termination::terminate_instance;
+use super::types::{
+    ExitReason, HookCommand, HookFailure, ProcessConfig, ProcessEvent, ResourceLimits,
+};
+
+/// 钩子命令的输出类型
+const HOOK_OUTPUT_TYPE: &str = "hook";
+
+/// 等待钩子命令退出时的轮询间隔
+const HOOK_POLL_INTERVAL_MS: u64 = 50;
+
+/// 钩子执行阶段
+#[derive(Debug, Clone, Copy)]
+pub(super) enum HookStage {
+    PreStart,
+    PostStart,
+    PreStop,
+    PostStop,
+}
+
+impl HookStage {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HookStage::PreStart => "pre-start",
+            HookStage::PostStart => "post-start",
+            HookStage::PreStop => "pre-stop",
+            HookStage::PostStop => "post-stop",
+        }
+    }
+
+    /// 进程配置中该阶段的钩子命令
+    fn hooks<'a>(&self, config: &'a ProcessConfig) -> &'a [HookCommand] {
+        match self {
+            HookStage::PreStart => &config.pre_start,
+            HookStage::PostStart => &config.post_start,
+            HookStage::PreStop => &config.pre_stop,
+            HookStage::PostStop => &config.post_stop,
+        }
+    }
+}
+
+/// 依次执行进程在某阶段的钩子命令
+///
+/// `deadline` 为所有钩子最晚结束的时间（退出应用时使用），超过后钩子命令被结束。
+/// 设置为 fail 的钩子失败时中止后续钩子并返回错误
+pub(super) fn run_hooks(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    config: &ProcessConfig,
+    stage: HookStage,
+    deadline: Option<Instant>,
+) -> Result<(), String> {
+    for hook in stage.hooks(config) {
+        let Err(error) = run_hook(app, manager, id, config, stage, hook, deadline) else {
+            continue;
+        };
+        eprintln!(
+            "{} hook of process {} failed: {}",
+            stage.as_str(),
+            id,
+            error
+        );
+        push_hook_line(app, manager, id, stage, &error);
+        if hook.on_failure == HookFailure::Fail {
+            return Err(format!("{} hook failed: {}", stage.as_str(), error));
+        }
+    }
+    Ok(())
+}
+
+/// 执行启动后的钩子，失败时发送启动失败事件并结束该运行实例
+pub(super) fn run_post_start_hooks(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    config: &ProcessConfig,
+) -> Result<(), String> {
+    run_hooks(app, manager, id, config, HookStage::PostStart, None).inspect_err(|error| {
+        emit_process_event(
+            app,
+            id,
+            ProcessEvent::StartFailed {
+                error: error.clone(),
+            },
+        );
+        if let Some(event) = terminate_instance(app, manager, id, pid, ExitReason::HookFailed) {
+            publish_exits(app, std::slice::from_ref(&event));
+            run_stop_hooks(app, manager, id, HookStage::PostStop);
+        }
+    })
+}
+
+/// 按进程的最新配置执行停止前或停止后的钩子（进程自行退出或被内部流程结束时使用），
+/// 失败只记录到输出
+pub(super) fn run_stop_hooks(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    stage: HookStage,
+) {
+    let config = match manager.lock() {
+        Ok(state) => state.configs.get(id).cloned(),
+        Err(_) => return,
+    };
+    if let Some(config) = config {
+        let _ = run_hooks(app, manager, id, &config, stage, None);
+    }
+}
+
+/// 并行执行多个进程在某阶段的钩子（退出应用时使用），失败时只记录到输出
+pub(super) fn run_hooks_parallel(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    configs: &[ProcessConfig],
+    stage: HookStage,
+    deadline: Instant,
+) {
+    thread::scope(|scope| {
+        for config in configs
+            .iter()
+            .filter(|config| !stage.hooks(config).is_empty())
+        {
+            scope.spawn(move || {
+                let _ = run_hooks(app, manager, &config.id, config, stage, Some(deadline));
+            });
+        }
+    });
+}
+
+/// 执行单个钩子命令并等待其退出
+fn run_hook(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    config: &ProcessConfig,
+    stage: HookStage,
+    hook: &HookCommand,
+    deadline: Option<Instant>,
+) -> Result<(), String> {
+    // 复用进程的启动方式：相同的工作目录、环境变量和进程组（不使用伪终端和资源限制）
+    let hook_config = ProcessConfig {
+        command_type: hook.command_type.clone(),
+        command: hook.command.clone(),
+        args: hook.args.clone(),
+        use_pty: false,
+        resource_limits: ResourceLimits::default(),
+        ..config.clone()
+    };
+    let command_line = std::iter::once(&hook.command)
+        .chain(&hook.args)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ");
+    push_hook_line(app, manager, id, stage, &format!("$ {}", command_line));
+
+    let mut spawned = spawn_process(&hook_config, None)?;
+    // 钩子命令不接收输入：立即关闭标准输入，读取输入的命令会读到 EOF 而不是一直阻塞到超时
+    drop(spawned.child.stdin.take());
+    let stdout = spawned.child.stdout.take();
+    let stderr = spawned.child.stderr.take();
+    let readers = [
+        stdout.map(|pipe| read_hook_output(app, manager, id, stage, pipe)),
+        stderr.map(|pipe| read_hook_output(app, manager, id, stage, pipe)),
+    ];
+
+    let started = Instant::now();
+    let timeout = started + Duration::from_millis(hook.timeout_ms);
+    // 退出应用的截止时间早于钩子自身的超时时，超时后报告实际生效的限制
+    let cut_by_deadline = deadline.is_some_and(|deadline| deadline < timeout);
+    let deadline = deadline.map_or(timeout, |deadline| deadline.min(timeout));
+    let result = loop {
+        match spawned.child.try_wait() {
+            Ok(Some(status)) => {
+                break match status.code() {
+                    Some(0) => Ok(()),
+                    Some(code) => Err(format!("Hook command exited with code {}", code)),
+                    None => Err("Hook command was terminated by a signal".to_string()),
+                };
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                spawned.tree.kill();
+                let _ = spawned.child.kill();
+                let _ = spawned.child.wait();
+                break Err(if cut_by_deadline {
+                    format!(
+                        "Hook command was stopped by the shutdown timeout after {} ms",
+                        started.elapsed().as_millis()
+                    )
+                } else {
+                    format!("Hook command timed out after {} ms", hook.timeout_ms)
+                });
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(HOOK_POLL_INTERVAL_MS)),
+            Err(e) => break Err(e.to_string()),
+        }
+    };
+    // 结束钩子命令遗留的子进程，保证输出管道关闭
+    spawned.tree.kill();
+    for reader in readers.into_iter().flatten() {
+        let _ = reader.join();
+    }
+    result
+}
+
+/// 在后台线程中读取钩子命令的输出
+fn read_hook_output(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    stage: HookStage,
+    pipe: impl Read + Send + 'static,
+) -> JoinHandle<()> {
+    let app = app.clone();
+    let manager = manager.clone();
+    let id = id.to_string();
+    thread::spawn(move || {
+        for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+            push_hook_line(&app, &manager, &id, stage, &line);
+        }
+    })
+}
+
+/// 记录一行钩子输出，带上执行阶段前缀
+fn push_hook_line(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    stage: HookStage,
+    line: &str,
+) {
+    let line = format!("[{}] {}", stage.as_str(), line);
+    push_output_line(app, manager, id, HOOK_OUTPUT_TYPE, &line);
+}
@@ -0,0 +1,75 @@
+//! 进程输入命令
+//!
+//! 本实例启动的进程通过管道连接标准输入（伪终端模式下为伪终端），可以向游戏服务器、
+//! REPL 等进程发送控制台命令。发送的每一行以 `stdin` 类型记录到进程输出中
+
+use tauri::AppHandle;
+
+use super::runner::push_output_line;
+use super::state::ProcessManager;
+use super::types::PtySize;
+
+/// 向运行中的进程写入输入，末尾没有换行时自动补上
+#[tauri::command]
+pub async fn send_process_input(
+    app: AppHandle,
+    state: tauri::State<'_, ProcessManager>,
+    id: String,
+    text: String,
+) -> Result<(), String> {
+    let stdin = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        let running = manager
+            .processes
+            .get(&id)
+            .ok_or_else(|| "Process is not running".to_string())?;
+        running.stdin.clone().ok_or_else(|| {
+            "Process does not accept input (detached or adopted process)".to_string()
+        })?
+    };
+
+    // 进程不读取输入时管道写满会阻塞，在阻塞线程中写入
+    let manager = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut data = text;
+        if !data.ends_with('\n') {
+            data.push('\n');
+        }
+        {
+            let mut stdin = stdin.lock().map_err(|e| e.to_string())?;
+            stdin
+                .write_all(data.as_bytes())
+                .and_then(|_| stdin.flush())
+                .map_err(|e| format!("Failed to write to process input: {}", e))?;
+        }
+        for line in data.lines() {
+            push_output_line(&app, &manager, &id, "stdin", line);
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 调整在伪终端中运行的进程的窗口大小
+#[tauri::command]
+pub fn resize_process_pty(
+    state: tauri::State<'_, ProcessManager>,
+    id: String,
+    rows: u16,
+    cols: u16,
+) -> Result<(), String> {
+    if rows == 0 || cols == 0 {
+        return Err("PTY size must be greater than zero".to_string());
+    }
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    let running = manager
+        .processes
+        .get(&id)
+        .ok_or_else(|| "Process is not running".to_string())?;
+    running
+        .pty
+        .as_ref()
+        .ok_or_else(|| "Process is not running in a pseudo-terminal".to_string())?
+        .resize(PtySize { rows, cols })
+}
@@ -1,131 +1,485 @@
 //! 进程生命周期管理命令
 
-use tauri::{AppHandle, Emitter};
-
-use super::runner::{register_running_process, setup_output_listeners, spawn_process};
-use super::state::ProcessManager;
-use super::types::{ProcessConfig, ProcessInfo, ProcessStatus};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use super::adopt::record_instance;
+use super::dependencies::{dependents, shutdown_layers, start_order};
+use super::detach::{detached_log_files, follow_log_files};
+use super::events::emit_process_event;
+use super::exit::{classify_exit, exit_info, publish_exits, record_exit};
+use super::health::start_health_check;
+use super::hooks::{run_hooks, run_post_start_hooks, HookStage};
+use super::query::build_process_info;
+use super::readiness::{mark_ready, wait_for_ready, wait_until_ready, watch_readiness};
+use super::runner::{register_running_process, setup_output_listeners, spawn_process, SpawnResult};
+use super::stale::{cleanup_stale_instances, write_pid_file};
+use super::state::{ProcessManager, ProcessManagerState};
+use super::task::wait_for_task;
+use super::termination::terminate_child;
+use super::types::{
+    ExitReason, ProcessConfig, ProcessEvent, ProcessExitedEvent, ProcessInfo, ProcessKind,
+    RestartPolicy, StaleCleanup, StopOutcome, StopResult,
+};
+use super::utils::current_timestamp_millis;
+use super::waiter::ExitWaiter;
+use crate::storage::{
+    load_auto_start_delay_secs, load_user_stopped_ids, set_process_user_stopped, DbState,
+};
+
+/// 应用启动时间，用于计算全局自动启动延迟
+static APP_LAUNCHED_AT: OnceLock<Instant> = OnceLock::new();
 
 /// 启动进程
+///
+/// 先按依赖顺序启动未运行的依赖，设置了等待依赖时在依赖就绪后再启动本进程
 #[tauri::command]
-pub fn start_process(
+pub async fn start_process(
     app: AppHandle,
-    state: tauri::State<ProcessManager>,
+    state: tauri::State<'_, ProcessManager>,
+    db_state: tauri::State<'_, DbState>,
     id: String,
 ) -> Result<ProcessInfo, String> {
-    let config: ProcessConfig;
+    // 等待依赖就绪可能耗时较长，在阻塞线程中执行
+    let manager = state.inner().clone();
+    let (started, info) =
+        tauri::async_runtime::spawn_blocking(move || start_with_dependencies(&app, &manager, &id))
+            .await
+            .map_err(|e| e.to_string())??;
+
     {
-        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+        for id in &started {
+            set_process_user_stopped(&conn, id, false)?;
+        }
+    }
+
+    Ok(info)
+}
 
-        if manager.processes.contains_key(&id) {
+/// 启动进程及其未运行的依赖，返回实际启动的进程 ID（依赖在前）和进程信息
+///
+/// 上次运行已成功完成的任务不会随依赖它的进程再次运行（例如数据库迁移），需要重新运行时手动启动该任务
+pub(super) fn start_with_dependencies(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+) -> Result<(Vec<String>, ProcessInfo), String> {
+    let order = {
+        let state = manager.lock().map_err(|e| e.to_string())?;
+        if state.processes.contains_key(id) {
             return Err("Process is already running".to_string());
         }
+        if state.starting.contains(id) {
+            return Err("Process is already starting".to_string());
+        }
+        if !state.configs.contains_key(id) {
+            return Err("Process config not found".to_string());
+        }
+        start_order(&state.configs, &[id.to_string()])?
+    };
+
+    let mut started = Vec::new();
+    for dep in order.iter().filter(|dep| *dep != id) {
+        let skip = manager
+            .lock()
+            .map(|state| state.processes.contains_key(dep) || task_completed(&state, dep))
+            .map_err(|e| e.to_string())?;
+        if skip {
+            continue;
+        }
+        start_process_with_manager(app, manager, dep).map_err(|e| {
+            format!(
+                "Failed to start dependency {}: {}",
+                process_name(manager, dep),
+                e
+            )
+        })?;
+        started.push(dep.clone());
+    }
+
+    // 手动启动时取消待执行的自动重启并重置退避计数
+    let guard = StartingGuard::acquire(manager, id)?
+        .ok_or_else(|| "Process is already running or starting".to_string())?;
+    start_guarded(app, manager, &guard)?;
+    started.push(id.to_string());
+
+    let state = manager.lock().map_err(|e| e.to_string())?;
+    let config = state
+        .configs
+        .get(id)
+        .ok_or_else(|| "Process config not found".to_string())?;
+    Ok((started, build_process_info(&state, config)))
+}
+
+/// 是否为上次运行已成功完成的任务
+fn task_completed(state: &ProcessManagerState, id: &str) -> bool {
+    state
+        .configs
+        .get(id)
+        .is_some_and(|c| c.kind == ProcessKind::Task)
+        && state
+            .runtime
+            .get(id)
+            .and_then(|r| r.last_exit.as_ref())
+            .is_some_and(|exit| exit.reason == ExitReason::Exited)
+}
 
-        config = manager
+/// 启动前等待依赖就绪（设置了 `wait_for_dependencies` 时）
+///
+/// 依赖为任务时等待任务成功完成
+fn await_dependencies(manager: &ProcessManager, config: &ProcessConfig) -> Result<(), String> {
+    if !config.wait_for_dependencies {
+        return Ok(());
+    }
+    for dep in &config.depends_on {
+        let is_task = manager
+            .lock()
+            .map_err(|e| e.to_string())?
             .configs
-            .get(&id)
-            .ok_or_else(|| "Process config not found".to_string())?
-            .clone();
+            .get(dep)
+            .is_some_and(|c| c.kind == ProcessKind::Task);
+        let result = if is_task {
+            wait_for_task(manager, dep)
+        } else {
+            wait_for_ready(manager, dep)
+        };
+        result.map_err(|e| {
+            format!(
+                "Dependency {} is not ready: {}",
+                process_name(manager, dep),
+                e
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// 获取进程名称（用于错误信息），找不到配置时返回 ID
+fn process_name(manager: &ProcessManager, id: &str) -> String {
+    manager
+        .lock()
+        .ok()
+        .and_then(|state| state.configs.get(id).map(|c| c.name.clone()))
+        .unwrap_or_else(|| id.to_string())
+}
 
-        if let Some(output) = manager.outputs.get_mut(&id) {
-            output.clear();
+/// 按依赖关系排列需要启动的进程，依赖在前，其余按启动优先级和创建时间排列
+fn ordered_with_dependencies(state: &ProcessManagerState, mut ids: Vec<String>) -> Vec<String> {
+    ids.sort_by_key(|id| state.configs.get(id).map(|c| (c.start_order, c.created_at)));
+    match start_order(&state.configs, &ids) {
+        Ok(order) => order,
+        Err(e) => {
+            eprintln!("Failed to order processes by dependencies: {}", e);
+            ids
         }
     }
+}
 
-    let mut result = spawn_process(&config)?;
-    let pid = result.child.id();
-    let started_at = result.started_at;
-
-    // 设置输出监听
-    setup_output_listeners(&app, state.inner(), &id, &mut result.child);
+/// 距离全局自动启动延迟（应用启动后 N 秒）结束的剩余时间
+fn remaining_auto_start_delay(app: &AppHandle) -> Duration {
+    let delay_secs = app
+        .try_state::<DbState>()
+        .and_then(|db| {
+            db.0.lock()
+                .ok()
+                .map(|conn| load_auto_start_delay_secs(&conn))
+        })
+        .unwrap_or(0);
+    let launched_at = APP_LAUNCHED_AT.get_or_init(Instant::now);
+    Duration::from_secs(delay_secs).saturating_sub(launched_at.elapsed())
+}
 
-    // 注册运行中的进程
-    register_running_process(state.inner(), &id, result.child, config.clone(), started_at)?;
-
-    let _ = app.emit("process-status-changed", &id);
-
-    Ok(ProcessInfo {
-        id: config.id,
-        name: config.name,
-        mode: config.mode,
-        command_type: config.command_type,
-        command: config.command,
-        args: config.args,
-        working_dir: config.working_dir,
-        source_path: config.source_path,
-        status: ProcessStatus::Running,
-        pid: Some(pid),
-        auto_restart: config.auto_restart,
-        auto_start: config.auto_start,
-        started_at: Some(started_at),
-        created_at: config.created_at,
-        has_output: false,
-    })
+/// 自动启动时等待进程的启动延迟
+fn wait_start_delay(manager: &ProcessManager, id: &str) {
+    let delay_ms = manager
+        .lock()
+        .ok()
+        .and_then(|state| state.configs.get(id).map(|c| c.start_delay_ms))
+        .unwrap_or(0);
+    if delay_ms > 0 {
+        std::thread::sleep(Duration::from_millis(delay_ms));
+    }
 }
 
 /// 停止进程
+///
+/// 先发送配置的停止信号，超时后强制结束，返回进程的停止方式
 #[tauri::command]
-pub fn stop_process(
+pub async fn stop_process(
     app: AppHandle,
-    state: tauri::State<ProcessManager>,
+    state: tauri::State<'_, ProcessManager>,
+    db_state: tauri::State<'_, DbState>,
     id: String,
-) -> Result<(), String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+) -> Result<StopResult, String> {
+    // 等待进程退出可能耗时较长，在阻塞线程中执行
+    let manager = state.inner().clone();
+    let stop_id = id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        stop_process_with_manager(&app, &manager, &stop_id)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    // 停止成功后记录用户手动停止，unless-stopped 策略的进程在应用下次启动时不再启动
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    set_process_user_stopped(&conn, &id, true)?;
+    Ok(result)
+}
 
-    let mut running = manager
-        .processes
-        .remove(&id)
-        .ok_or_else(|| "Process is not running".to_string())?;
+/// 停止进程并等待其退出（阻塞直到进程退出或被强制结束）
+///
+/// 运行中的进程在停止前后执行钩子，停止前的钩子失败时不停止进程
+pub(super) fn stop_process_with_manager(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+) -> Result<StopResult, String> {
+    let hooks_config = {
+        let state = manager.lock().map_err(|e| e.to_string())?;
+        state
+            .processes
+            .contains_key(id)
+            .then(|| state.configs.get(id).cloned())
+            .flatten()
+    };
+    if let Some(config) = &hooks_config {
+        run_hooks(app, manager, id, config, HookStage::PreStop, None)?;
+    }
 
-    running
-        .child
-        .kill()
-        .map_err(|e| format!("Failed to stop process: {}", e))?;
+    let (mut running, signal, timeout_ms) = {
+        let mut state = manager.lock().map_err(|e| e.to_string())?;
 
-    let _ = app.emit("process-status-changed", &id);
+        // 处于重启等待中的进程：取消待执行的自动重启
+        let pending_restart = state
+            .runtime
+            .get_mut(id)
+            .map(|r| r.reset_restart())
+            .unwrap_or(false);
 
-    Ok(())
+        let Some(running) = state.processes.remove(id) else {
+            if !pending_restart {
+                return Err("Process is not running".to_string());
+            }
+            let signal = state
+                .configs
+                .get(id)
+                .map(|c| c.stop_signal)
+                .unwrap_or_default();
+            drop(state);
+            emit_process_event(app, id, ProcessEvent::RestartCancelled);
+            return Ok(StopResult {
+                id: id.to_string(),
+                outcome: StopOutcome::AlreadyExited,
+                signal,
+                elapsed_ms: 0,
+            });
+        };
+
+        // 优先使用最新配置，进程在运行期间可能修改过停止设置
+        let config = state.configs.get(id).unwrap_or(&running.config);
+        let (signal, timeout_ms) = (config.stop_signal, config.stop_timeout_ms);
+        (running, signal, timeout_ms)
+    };
+
+    emit_process_event(app, id, ProcessEvent::Stopping { signal });
+
+    let termination = terminate_child(
+        &mut running.child,
+        &running.tree,
+        signal,
+        Duration::from_millis(timeout_ms),
+    );
+    println!(
+        "Stopped process {} ({:?} after {} ms)",
+        id, termination.outcome, termination.elapsed_ms
+    );
+
+    let status = termination.status.as_ref();
+    let reason = match termination.outcome {
+        StopOutcome::Signaled => ExitReason::UserStopped,
+        StopOutcome::ForceKilled => ExitReason::KilledByTimeout,
+        StopOutcome::AlreadyExited => {
+            classify_exit(&running.config, status, running.cgroup.as_ref())
+        }
+    };
+    let event = ProcessExitedEvent {
+        id: id.to_string(),
+        pid: running.child.id(),
+        exit: exit_info(status, reason, running.launched_at),
+    };
+    if let Ok(mut state) = manager.lock() {
+        record_exit(&mut state, id, &event.exit);
+    }
+    publish_exits(app, std::slice::from_ref(&event));
+    if let Some(config) = &hooks_config {
+        // 进程已停止，停止后的钩子失败只记录到输出
+        let _ = run_hooks(app, manager, id, config, HookStage::PostStop, None);
+    }
+
+    Ok(StopResult {
+        id: id.to_string(),
+        outcome: termination.outcome,
+        signal,
+        elapsed_ms: termination.elapsed_ms,
+    })
 }
 
-/// 启动所有设置为跟随应用启动的进程（Tauri 命令）
+/// 停止进程及所有依赖它的进程
+///
+/// 按依赖关系的逆序停止：先停止依赖它的进程，互不依赖的进程并行停止
 #[tauri::command]
-pub fn start_auto_start_processes(
+pub async fn stop_process_with_dependents(
     app: AppHandle,
-    state: tauri::State<ProcessManager>,
-) -> Result<Vec<String>, String> {
-    let auto_start_ids: Vec<String>;
-    {
+    state: tauri::State<'_, ProcessManager>,
+    db_state: tauri::State<'_, DbState>,
+    id: String,
+) -> Result<Vec<StopResult>, String> {
+    let layers = {
         let manager = state.lock().map_err(|e| e.to_string())?;
-        auto_start_ids = manager
-            .configs
-            .iter()
-            .filter(|(_, config)| config.auto_start)
-            .map(|(id, _)| id.clone())
-            .collect();
+        if !manager.configs.contains_key(&id) {
+            return Err("Process not found".to_string());
+        }
+        let mut ids = dependents(&manager.configs, &id);
+        ids.push(id.clone());
+        // 只停止运行中或等待重启的进程
+        ids.retain(|id| {
+            manager.processes.contains_key(id)
+                || manager
+                    .runtime
+                    .get(id)
+                    .is_some_and(|r| r.next_restart_at.is_some())
+        });
+        shutdown_layers(&manager.configs, &ids)
+    };
+
+    let manager = state.inner().clone();
+    let results = tauri::async_runtime::spawn_blocking(move || {
+        let mut results = Vec::new();
+        for layer in layers {
+            let stopped: Vec<Result<StopResult, String>> = std::thread::scope(|scope| {
+                let handles: Vec<_> = layer
+                    .iter()
+                    .map(|id| scope.spawn(|| stop_process_with_manager(&app, &manager, id)))
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| {
+                        h.join()
+                            .unwrap_or_else(|_| Err("Stop thread panicked".to_string()))
+                    })
+                    .collect()
+            });
+            // 进程可能在停止前已自行退出，忽略未运行的错误
+            results.extend(stopped.into_iter().filter_map(Result::ok));
+        }
+        results
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // 只为停止成功的进程记录用户手动停止
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    for result in &results {
+        set_process_user_stopped(&conn, &result.id, true)?;
     }
+    Ok(results)
+}
 
-    let mut started = Vec::new();
-    for id in auto_start_ids {
-        if start_process_with_manager(&app, state.inner(), &id).is_ok() {
-            started.push(id);
+/// 启动所有设置为跟随应用启动的进程（Tauri 命令）
+///
+/// 依赖会先于依赖它的进程启动，其余按启动优先级排列；
+/// 应用启动后的全局延迟尚未结束时先等待，每个进程启动前等待其启动延迟
+#[tauri::command]
+pub async fn start_auto_start_processes(
+    app: AppHandle,
+    state: tauri::State<'_, ProcessManager>,
+) -> Result<Vec<String>, String> {
+    let manager = state.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let order: Vec<String>;
+        {
+            let state = manager.lock().map_err(|e| e.to_string())?;
+            let auto_start_ids = state
+                .configs
+                .iter()
+                .filter(|(_, config)| config.auto_start)
+                .map(|(id, _)| id.clone())
+                .collect();
+            order = ordered_with_dependencies(&state, auto_start_ids);
+        }
+
+        std::thread::sleep(remaining_auto_start_delay(&app));
+
+        let mut started = Vec::new();
+        for id in order {
+            wait_start_delay(&manager, &id);
+            if start_process_with_manager(&app, &manager, &id).is_ok() {
+                started.push(id);
+            }
+        }
+        Ok(started)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 启动前检查隔离状态，并取消待执行的自动重启、重置退避计数
+fn prepare_start(state: &mut ProcessManagerState, id: &str) -> Result<(), String> {
+    if let Some(runtime) = state.runtime.get_mut(id) {
+        if runtime.quarantine.is_some() {
+            return Err(
+                "Process is quarantined after a crash loop. Clear the quarantine first."
+                    .to_string(),
+            );
         }
+        runtime.reset_restart();
     }
+    Ok(())
+}
 
-    Ok(started)
+/// 判断进程是否应在应用启动时启动
+///
+/// 除了设置了跟随应用启动的进程，always 策略的进程总是启动，
+/// unless-stopped 策略的进程在未被用户手动停止时启动
+fn starts_with_app(config: &ProcessConfig, user_stopped: bool) -> bool {
+    config.auto_start
+        || match config.restart_policy {
+            RestartPolicy::Always => true,
+            RestartPolicy::UnlessStopped => !user_stopped,
+            _ => false,
+        }
 }
 
 /// 应用启动时自动启动进程（供 setup 使用，不依赖 tauri::State）
-/// 在后台线程中排队启动，等待每个进程启动完成后再启动下一个
+/// 在后台线程中排队启动，等待每个进程启动完成后再启动下一个。
+/// 启动顺序与延迟规则同 `start_auto_start_processes`
 pub fn auto_start_processes_on_init(app: &AppHandle, manager: &ProcessManager) {
+    APP_LAUNCHED_AT.get_or_init(Instant::now);
+
+    let user_stopped: HashSet<String> = app
+        .try_state::<DbState>()
+        .and_then(|db| {
+            let conn = db.0.lock().ok()?;
+            load_user_stopped_ids(&conn).ok()
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
     let auto_start_ids: Vec<String>;
     {
         if let Ok(state) = manager.lock() {
-            auto_start_ids = state
+            let ids = state
                 .configs
                 .iter()
-                .filter(|(_, config)| config.auto_start)
+                .filter(|(id, config)| starts_with_app(config, user_stopped.contains(*id)))
                 .map(|(id, _)| id.clone())
                 .collect();
+            // 依赖先于依赖它的进程启动（包括未设置跟随应用启动的依赖）
+            auto_start_ids = ordered_with_dependencies(&state, ids);
         } else {
             return;
         }
@@ -139,7 +493,11 @@ pub fn auto_start_processes_on_init(app: &AppHandle, manager: &ProcessManager) {
     let app_clone = app.clone();
     let manager_clone = manager.clone();
     std::thread::spawn(move || {
+        // 应用启动后延迟一段时间再开始启动，避免与登录后的其他程序争抢资源
+        std::thread::sleep(remaining_auto_start_delay(&app_clone));
+
         for id in auto_start_ids.iter() {
+            wait_start_delay(&manager_clone, id);
             // 启动进程并等待确认启动状态
             match start_process_and_wait(&app_clone, &manager_clone, id) {
                 Ok(_) => {
@@ -154,100 +512,208 @@ pub fn auto_start_processes_on_init(app: &AppHandle, manager: &ProcessManager) {
     });
 }
 
-/// 启动进程并等待确认启动状态
-fn start_process_and_wait(
-    app: &AppHandle,
-    manager: &ProcessManager,
-    id: &str,
-) -> Result<(), String> {
-    let config: ProcessConfig;
-    {
-        let mut state = manager.lock().map_err(|e| e.to_string())?;
+/// 启动中标记
+///
+/// 在检查“是否已在运行”的同一次加锁中设置，释放时清除（包括所有错误路径），
+/// 防止重复点击、自动重启和定时任务在检查之后、注册之前同时启动同一进程
+struct StartingGuard {
+    manager: ProcessManager,
+    id: String,
+}
 
-        if state.processes.contains_key(id) {
-            return Ok(()); // 已经在运行
+impl StartingGuard {
+    /// 进程未运行且不在启动中时标记为启动中，否则返回 None；正在退出应用时返回错误
+    fn acquire(manager: &ProcessManager, id: &str) -> Result<Option<Self>, String> {
+        let mut state = manager.lock().map_err(|e| e.to_string())?;
+        if state.shutting_down {
+            return Err("Application is shutting down".to_string());
         }
-
-        config = state
-            .configs
-            .get(id)
-            .ok_or_else(|| "Process config not found".to_string())?
-            .clone();
-
-        if let Some(output) = state.outputs.get_mut(id) {
-            output.clear();
+        if state.processes.contains_key(id) || !state.starting.insert(id.to_string()) {
+            return Ok(None);
         }
+        Ok(Some(Self {
+            manager: manager.clone(),
+            id: id.to_string(),
+        }))
     }
+}
 
-    let mut result = spawn_process(&config)?;
-    let started_at = result.started_at;
-
-    // 等待一小段时间确认进程是否成功启动
-    std::thread::sleep(std::time::Duration::from_millis(100));
-
-    // 检查进程是否仍在运行
-    match result.child.try_wait() {
-        Ok(Some(status)) => {
-            // 进程已退出，启动失败
-            return Err(format!(
-                "Process exited immediately with status: {:?}",
-                status
-            ));
-        }
-        Ok(None) => {
-            // 进程仍在运行，启动成功
-        }
-        Err(e) => {
-            return Err(format!("Failed to check process status: {}", e));
+impl Drop for StartingGuard {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.manager.lock() {
+            state.starting.remove(&self.id);
         }
     }
+}
 
-    // 设置输出监听
-    setup_output_listeners(app, manager, id, &mut result.child);
+/// 读取配置、检查隔离状态并清空之前的输出（手动启动和自动启动时）
+fn prepare_manual_start(manager: &ProcessManager, id: &str) -> Result<ProcessConfig, String> {
+    let mut state = manager.lock().map_err(|e| e.to_string())?;
+    let config = state
+        .configs
+        .get(id)
+        .ok_or_else(|| "Process config not found".to_string())?
+        .clone();
 
-    // 注册运行中的进程
-    register_running_process(manager, id, result.child, config, started_at)?;
+    prepare_start(&mut state, id)?;
 
-    let _ = app.emit("process-status-changed", id);
+    if let Some(output) = state.outputs.get_mut(id) {
+        output.clear();
+    }
+    Ok(config)
+}
 
-    Ok(())
+/// 启动进程并等待确认启动状态
+fn start_process_and_wait(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+) -> Result<(), String> {
+    let Some(_guard) = StartingGuard::acquire(manager, id)? else {
+        return Ok(()); // 已经在运行或正在启动
+    };
+    let config = prepare_manual_start(manager, id)?;
+
+    await_dependencies(manager, &config)?;
+    cleanup_stale_instances(app, manager, id, &config);
+
+    emit_process_event(app, id, ProcessEvent::Starting);
+    let since_ms = current_timestamp_millis();
+    let result = run_hooks(app, manager, id, &config, HookStage::PreStart, None)
+        .and_then(|_| detached_log_files(app, id, &config))
+        .and_then(|log_files| spawn_process(&config, log_files.as_ref()))
+        .inspect_err(|e| emit_start_failed(app, id, e))?;
+    let pid = register_and_watch(app, manager, id, result, config.clone())
+        .inspect_err(|e| emit_start_failed(app, id, e))?;
+
+    // 等待进程满足就绪条件并执行启动后的钩子后再启动下一个进程
+    wait_until_ready(app, manager, id, pid, since_ms, &config)?;
+    run_post_start_hooks(app, manager, id, pid, &config)
 }
 
-/// 内部启动进程函数（通用版本）
+/// 内部启动进程函数（通用版本），进程已在运行或正在启动时直接返回
 fn start_process_with_manager(
     app: &AppHandle,
     manager: &ProcessManager,
     id: &str,
 ) -> Result<(), String> {
-    let config: ProcessConfig;
-    {
-        let mut state = manager.lock().map_err(|e| e.to_string())?;
+    match StartingGuard::acquire(manager, id)? {
+        Some(guard) => start_guarded(app, manager, &guard),
+        None => Ok(()),
+    }
+}
 
-        if state.processes.contains_key(id) {
-            return Ok(()); // 已经在运行
-        }
+/// 在持有启动中标记时启动进程
+fn start_guarded(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    guard: &StartingGuard,
+) -> Result<(), String> {
+    let config = prepare_manual_start(manager, &guard.id)?;
+    await_dependencies(manager, &config)?;
+    launch_process(app, manager, &guard.id, &config)
+}
 
-        config = state
-            .configs
-            .get(id)
-            .ok_or_else(|| "Process config not found".to_string())?
-            .clone();
+/// 自动重启进程（供守护线程使用）
+///
+/// 与手动启动不同，重启时保留之前的输出，便于排查崩溃原因
+pub(super) fn restart_process_with_manager(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+) -> Result<(), String> {
+    let Some(_guard) = StartingGuard::acquire(manager, id)? else {
+        return Ok(()); // 已经在运行或正在启动
+    };
+    let config = manager
+        .lock()
+        .map_err(|e| e.to_string())?
+        .configs
+        .get(id)
+        .ok_or_else(|| "Process config not found".to_string())?
+        .clone();
+
+    launch_process(app, manager, id, &config)
+}
 
-        if let Some(output) = state.outputs.get_mut(id) {
-            output.clear();
-        }
+/// 启动子进程、设置输出监听并注册到状态管理器
+fn launch_process(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    config: &ProcessConfig,
+) -> Result<(), String> {
+    cleanup_stale_instances(app, manager, id, config);
+
+    emit_process_event(app, id, ProcessEvent::Starting);
+    let since_ms = current_timestamp_millis();
+    let result = run_hooks(app, manager, id, config, HookStage::PreStart, None)
+        .and_then(|_| detached_log_files(app, id, config))
+        .and_then(|log_files| spawn_process(config, log_files.as_ref()))
+        .inspect_err(|e| emit_start_failed(app, id, e))?;
+    let pid = register_and_watch(app, manager, id, result, config.clone())
+        .inspect_err(|e| emit_start_failed(app, id, e))?;
+    if config.readiness.is_some() {
+        // 就绪后在后台执行启动后的钩子
+        watch_readiness(app, manager, id, pid, since_ms, config.clone());
+        return Ok(());
     }
+    run_post_start_hooks(app, manager, id, pid, config)
+}
 
-    let mut result = spawn_process(&config)?;
-    let started_at = result.started_at;
-
+/// 设置输出监听、注册运行中的进程并等待其退出，返回进程 PID
+///
+/// 未设置就绪条件的进程在启动后立即发送 ready 事件
+fn register_and_watch(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    mut result: SpawnResult,
+    config: ProcessConfig,
+) -> Result<u32, String> {
     // 设置输出监听
-    setup_output_listeners(app, manager, id, &mut result.child);
+    setup_output_listeners(app, manager, id, &mut result);
 
     // 注册运行中的进程
-    register_running_process(manager, id, result.child, config, started_at)?;
+    let pid = result.child.id();
+    let (started_at, detached) = (result.started_at, result.detached);
+    let waiter = ExitWaiter::new(&result.child);
+    let has_health_check = config.health_check.is_some();
+    let has_readiness = config.readiness.is_some();
+    let writes_pid_file = config.stale_cleanup == StaleCleanup::PidFile;
+    register_running_process(manager, id, result, config)?;
+    if writes_pid_file {
+        write_pid_file(app, id, pid);
+    }
+    record_instance(app, id, pid, started_at, detached);
+    if detached {
+        follow_log_files(app, manager, id, pid);
+    }
 
-    let _ = app.emit("process-status-changed", id);
+    emit_process_event(app, id, ProcessEvent::Started { pid });
+    if !has_readiness {
+        mark_ready(app, manager, id, pid);
+    }
 
-    Ok(())
+    // 在启动事件之后开始等待退出，保证事件顺序
+    match waiter {
+        Some(waiter) => waiter.start(app, manager, id),
+        None => eprintln!("Failed to watch process {} for exit", id),
+    }
+    if has_health_check {
+        start_health_check(app, manager, id, pid);
+    }
+
+    Ok(pid)
+}
+
+/// 发送启动失败事件
+fn emit_start_failed(app: &AppHandle, id: &str, error: &str) {
+    emit_process_event(
+        app,
+        id,
+        ProcessEvent::StartFailed {
+            error: error.to_string(),
+        },
+    );
 }
@@ -0,0 +1,300 @@
+//! 资源限制
+//!
+//! 进程可限制最大内存、CPU 配额、最多打开的文件数和最大进程数：
+//! - Linux 上存在委派给本应用的 cgroup v2 子树时，每个进程在独立的子 cgroup 中运行，
+//!   通过 memory.max / cpu.max / pids.max 限制整个进程树，进程被 OOM 结束时退出原因记录为 oom-killed
+//! - 否则在启动前（pre_exec）通过 setrlimit 限制：内存为虚拟内存上限（RLIMIT_AS），
+//!   进程数为所属用户的进程数上限（RLIMIT_NPROC），CPU 配额无法限制
+//! - 最多打开的文件数始终通过 RLIMIT_NOFILE 限制
+//!
+//! 其他平台不支持资源限制，配置的限制被忽略
+
+use std::process::Command;
+
+use super::types::ResourceLimits;
+
+/// 进程所在的子 cgroup
+#[cfg(target_os = "linux")]
+pub(crate) struct Cgroup {
+    path: std::path::PathBuf,
+    /// 创建时 memory.events 中的 oom_kill 计数（复用残留目录时不为 0）
+    oom_kills: u64,
+}
+
+/// 进程所在的子 cgroup（仅 Linux 支持，无法构造）
+#[cfg(not(target_os = "linux"))]
+pub(crate) enum Cgroup {}
+
+/// 配置启动命令的资源限制，返回进程所在的子 cgroup（未使用 cgroup 时为 None）
+pub(super) fn apply_resource_limits(
+    cmd: &mut Command,
+    id: &str,
+    limits: &ResourceLimits,
+) -> Result<Option<Cgroup>, String> {
+    if limits.is_empty() {
+        return Ok(None);
+    }
+    apply_platform_limits(cmd, id, limits)
+}
+
+#[cfg(target_os = "linux")]
+fn apply_platform_limits(
+    cmd: &mut Command,
+    id: &str,
+    limits: &ResourceLimits,
+) -> Result<Option<Cgroup>, String> {
+    let cgroup = if limits.needs_cgroup() {
+        cgroup::root().and_then(|root| {
+            Cgroup::create(root, id, limits)
+                .inspect_err(|e| eprintln!("Failed to create cgroup for process {}: {}", id, e))
+                .ok()
+        })
+    } else {
+        None
+    };
+
+    let mut rlimits = Vec::new();
+    if let Some(files) = limits.max_open_files {
+        rlimits.push((libc::RLIMIT_NOFILE, files));
+    }
+    if cgroup.is_none() {
+        if let Some(bytes) = limits.max_memory_bytes {
+            rlimits.push((libc::RLIMIT_AS, bytes));
+        }
+        if let Some(processes) = limits.max_processes {
+            rlimits.push((libc::RLIMIT_NPROC, processes));
+        }
+        if limits.cpu_quota_percent.is_some() {
+            eprintln!(
+                "CPU quota of process {} is ignored: no delegated cgroup v2 subtree",
+                id
+            );
+        }
+    }
+
+    let join = cgroup
+        .as_ref()
+        .map(|cgroup| cgroup.procs_file())
+        .transpose()
+        .map_err(|e| format!("Failed to join cgroup: {}", e))?;
+
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+    unsafe {
+        cmd.pre_exec(move || {
+            // 写入 "0" 将当前进程移入该 cgroup，之后派生的子孙进程都属于它
+            if let Some(procs) = &join {
+                if libc::write(procs.as_raw_fd(), b"0".as_ptr().cast(), 1) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            for (resource, value) in &rlimits {
+                let limit = libc::rlimit {
+                    rlim_cur: *value as libc::rlim_t,
+                    rlim_max: *value as libc::rlim_t,
+                };
+                if libc::setrlimit(*resource, &limit) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+            }
+            Ok(())
+        });
+    }
+    Ok(cgroup)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_platform_limits(
+    _cmd: &mut Command,
+    id: &str,
+    _limits: &ResourceLimits,
+) -> Result<Option<Cgroup>, String> {
+    eprintln!(
+        "Resource limits of process {} are only supported on Linux",
+        id
+    );
+    Ok(None)
+}
+
+/// 子 cgroup 的目录名：对进程 ID 做十六进制编码，不同的 ID 不会映射到同一目录
+#[cfg(target_os = "linux")]
+fn cgroup_dir_name(id: &str) -> String {
+    let hex: String = id.bytes().map(|b| format!("{:02x}", b)).collect();
+    format!("process-{}", hex)
+}
+
+#[cfg(target_os = "linux")]
+impl Cgroup {
+    /// 在委派的子树中创建（或复用残留的）子 cgroup 并写入限制
+    fn create(root: &std::path::Path, id: &str, limits: &ResourceLimits) -> std::io::Result<Self> {
+        use std::fs;
+
+        let path = root.join(cgroup_dir_name(id));
+        match fs::create_dir(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e),
+            _ => {}
+        }
+
+        let cpu = limits.cpu_quota_percent.map(|percent| {
+            let quota = u64::from(percent) * cgroup::CPU_PERIOD_US / 100;
+            format!("{} {}", quota, cgroup::CPU_PERIOD_US)
+        });
+        let settings = [
+            ("memory.max", limits.max_memory_bytes.map(|v| v.to_string())),
+            ("pids.max", limits.max_processes.map(|v| v.to_string())),
+            ("cpu.max", cpu),
+        ];
+        let result = settings.into_iter().try_for_each(|(file, value)| {
+            let file = path.join(file);
+            match value {
+                Some(value) => fs::write(&file, value),
+                // 未设置的限制写入 max，覆盖复用的目录中之前的值
+                None if file.exists() => fs::write(&file, "max"),
+                None => Ok(()),
+            }
+        });
+        // 所需的控制器未启用时无法写入，改用 setrlimit
+        if let Err(e) = result {
+            let _ = fs::remove_dir(&path);
+            return Err(e);
+        }
+
+        let oom_kills = cgroup::oom_kills(&path);
+        Ok(Self { path, oom_kills })
+    }
+
+    /// 以写方式打开 cgroup.procs，在 fork 之前打开以便 pre_exec 中直接写入
+    fn procs_file(&self) -> std::io::Result<std::fs::File> {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(self.path.join("cgroup.procs"))
+    }
+
+    /// 进程树在本次运行中是否有进程因超出内存限制被 OOM 结束
+    pub fn oom_killed(&self) -> bool {
+        cgroup::oom_kills(&self.path) > self.oom_kills
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Cgroup {
+    pub fn oom_killed(&self) -> bool {
+        match *self {}
+    }
+}
+
+/// 进程退出后删除子 cgroup（仍有残留进程时删除失败，下次启动时复用）
+#[cfg(target_os = "linux")]
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir(&self.path);
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod cgroup {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::sync::OnceLock;
+
+    /// cpu.max 的周期（微秒）
+    pub const CPU_PERIOD_US: u64 = 100_000;
+
+    /// 需要在子 cgroup 中启用的控制器
+    const CONTROLLERS: [&str; 3] = ["memory", "cpu", "pids"];
+
+    /// 本应用自身的进程所在的叶子 cgroup
+    const LEAF_NAME: &str = "launcher";
+
+    static ROOT: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+    /// 委派给本应用的 cgroup v2 子树（本应用所在的 cgroup），首次使用时初始化
+    pub fn root() -> Option<&'static Path> {
+        ROOT.get_or_init(|| {
+            init_root()
+                .inspect_err(|e| {
+                    eprintln!("cgroup v2 resource limits are unavailable: {}", e);
+                })
+                .ok()
+        })
+        .as_deref()
+    }
+
+    /// 定位本应用所在的 cgroup 并启用子 cgroup 的控制器
+    ///
+    /// cgroup v2 不允许同时包含进程和启用了控制器的子 cgroup，
+    /// 因此先将本 cgroup 中的进程（本应用及其已启动的子进程）移入叶子 cgroup
+    fn init_root() -> Result<PathBuf, String> {
+        let mount = fs::read_to_string("/proc/self/mountinfo")
+            .map_err(|e| e.to_string())?
+            .lines()
+            .find_map(|line| {
+                let (fields, fs_type) = line.split_once(" - ")?;
+                if !fs_type.starts_with("cgroup2 ") {
+                    return None;
+                }
+                fields.split_whitespace().nth(4).map(PathBuf::from)
+            })
+            .ok_or("cgroup v2 is not mounted")?;
+        let relative = fs::read_to_string("/proc/self/cgroup")
+            .map_err(|e| e.to_string())?
+            .lines()
+            .find_map(|line| line.strip_prefix("0::").map(str::to_string))
+            .ok_or("process is not in a cgroup v2 hierarchy")?;
+        let own = mount.join(relative.trim_start_matches('/'));
+
+        let available =
+            fs::read_to_string(own.join("cgroup.controllers")).map_err(|e| e.to_string())?;
+        let enabled: Vec<String> = CONTROLLERS
+            .iter()
+            .filter(|c| available.split_whitespace().any(|a| a == **c))
+            .map(|c| format!("+{}", c))
+            .collect();
+        if enabled.is_empty() {
+            return Err("no memory, cpu or pids controller is delegated".to_string());
+        }
+
+        // 根 cgroup 不受该限制，也不应移动系统中的其他进程
+        if relative.trim() != "/" {
+            let leaf = own.join(LEAF_NAME);
+            if let Err(e) = fs::create_dir(&leaf) {
+                if e.kind() != std::io::ErrorKind::AlreadyExists {
+                    return Err(e.to_string());
+                }
+            }
+            let procs = fs::read_to_string(own.join("cgroup.procs")).map_err(|e| e.to_string())?;
+            // 期间退出的进程移动失败，仍有进程未移动时下面启用控制器会失败
+            for pid in procs.lines() {
+                let _ = fs::write(leaf.join("cgroup.procs"), pid);
+            }
+        }
+        fs::write(own.join("cgroup.subtree_control"), enabled.join(" "))
+            .map_err(|e| e.to_string())?;
+        Ok(own)
+    }
+
+    /// memory.events 中的 oom_kill 计数
+    pub fn oom_kills(path: &Path) -> u64 {
+        fs::read_to_string(path.join("memory.events"))
+            .ok()
+            .and_then(|events| {
+                events.lines().find_map(|line| {
+                    line.strip_prefix("oom_kill ")
+                        .and_then(|count| count.trim().parse().ok())
+                })
+            })
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cgroup_dir_names_do_not_collide() {
+        assert_eq!(cgroup_dir_name("api"), "process-617069");
+        assert_ne!(cgroup_dir_name("a.b"), cgroup_dir_name("a-b"));
+        assert_ne!(cgroup_dir_name("a b"), cgroup_dir_name("a_b"));
+    }
+}
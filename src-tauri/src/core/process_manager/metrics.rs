@@ -0,0 +1,330 @@
+//! 资源占用采样
+//!
+//! 采样线程定期读取每个运行中的进程及其所有子孙进程的 `/proc/<pid>/stat`、`status` 和 `io`，
+//! 合计 CPU 占用、常驻内存、线程数和存储读写速率。结果作为进程的当前资源占用
+//! （通过 `get_process` / `list_processes` 获取），并发送 `process-metrics` 事件。
+//! 子孙进程包括父进程已退出、但仍在同一进程组中的进程。仅支持 Linux
+//!
+//! 采样结果同时保存到数据库：原始数据保留 1 小时，每分钟汇总为 1 分钟平均值（保留 1 天）
+//! 和 15 分钟平均值（保留 30 天），通过 `get_process_metrics` 按时间范围查询。
+//! 每次采样后检查进程的资源阈值规则
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+use super::state::ProcessManager;
+
+/// 采样间隔
+#[cfg(target_os = "linux")]
+const METRICS_INTERVAL_MS: u64 = 2000;
+
+/// 汇总历史数据的间隔
+#[cfg(target_os = "linux")]
+const COMPACT_INTERVAL_SECS: u64 = 60;
+
+static SAMPLER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 启动资源占用采样线程
+pub fn start_metrics_sampler(app: &AppHandle, manager: &ProcessManager) {
+    // 防止重复启动采样线程
+    if SAMPLER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let app = app.clone();
+        let manager = manager.clone();
+        std::thread::spawn(move || {
+            let mut sampler = linux::Sampler::default();
+            loop {
+                std::thread::sleep(std::time::Duration::from_millis(METRICS_INTERVAL_MS));
+                sampler.sample(&app, &manager);
+            }
+        });
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (app, manager);
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::sync::LazyLock;
+    use std::time::{Duration, Instant};
+    use tauri::{AppHandle, Emitter, Manager};
+
+    use crate::core::process_manager::state::ProcessManager;
+    use crate::core::process_manager::thresholds::ThresholdMonitor;
+    use crate::core::process_manager::types::{ProcessMetrics, ProcessMetricsEvent};
+    use crate::core::process_manager::utils::current_timestamp_millis;
+    use crate::storage::{compact_process_metrics, save_process_metrics, DbState};
+
+    /// 每秒的时钟滴答数（`/proc/<pid>/stat` 中 CPU 时间的单位）
+    static CLOCK_TICKS: LazyLock<f64> = LazyLock::new(|| {
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        if ticks > 0 {
+            ticks as f64
+        } else {
+            100.0
+        }
+    });
+
+    /// 单个进程的累计计数，两次采样之差即该时间段内的用量
+    #[derive(Clone, Copy, Default)]
+    struct Counters {
+        cpu_ticks: u64,
+        read_bytes: u64,
+        write_bytes: u64,
+    }
+
+    /// `/proc/<pid>/stat` 中用到的字段
+    struct ProcStat {
+        pid: u32,
+        ppid: u32,
+        pgrp: u32,
+        /// 用户态和内核态 CPU 时间（时钟滴答）
+        cpu_ticks: u64,
+    }
+
+    /// 某个运行实例上一次采样的结果
+    struct Previous {
+        pid: u32,
+        sampled: Instant,
+        counters: HashMap<u32, Counters>,
+    }
+
+    /// 采样状态，保存每个进程上一次采样的累计计数
+    #[derive(Default)]
+    pub(super) struct Sampler {
+        previous: HashMap<String, Previous>,
+        /// 上一次汇总历史数据的时间
+        last_compacted: Option<Instant>,
+        thresholds: ThresholdMonitor,
+    }
+
+    impl Sampler {
+        /// 采样所有运行中的进程，保存结果并发送事件
+        pub fn sample(&mut self, app: &AppHandle, manager: &ProcessManager) {
+            let running: Vec<(String, u32)> = match manager.lock() {
+                Ok(state) => state
+                    .processes
+                    .iter()
+                    .map(|(id, running)| (id.clone(), running.child.id()))
+                    .collect(),
+                Err(_) => return,
+            };
+            // 丢弃已退出的运行实例的采样状态
+            self.previous.retain(|id, previous| {
+                running
+                    .iter()
+                    .any(|(running_id, pid)| running_id == id && *pid == previous.pid)
+            });
+            if running.is_empty() {
+                // 没有运行中的进程时仍需删除过期数据
+                self.persist(app, &[]);
+                return;
+            }
+
+            let stats = scan_processes();
+            let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+            for (index, stat) in stats.iter().enumerate() {
+                children.entry(stat.ppid).or_default().push(index);
+            }
+
+            let events: Vec<ProcessMetricsEvent> = running
+                .into_iter()
+                .filter_map(|(id, pid)| {
+                    let tree = process_tree(pid, &stats, &children);
+                    if tree.is_empty() {
+                        return None;
+                    }
+                    let metrics = self.measure(&id, pid, &tree);
+                    Some(ProcessMetricsEvent { id, pid, metrics })
+                })
+                .collect();
+
+            // 采样期间重启的进程不保存旧实例的结果
+            if let Ok(mut state) = manager.lock() {
+                for event in &events {
+                    if state
+                        .processes
+                        .get(&event.id)
+                        .is_some_and(|running| running.child.id() == event.pid)
+                    {
+                        state.runtime.entry(event.id.clone()).or_default().metrics =
+                            Some(event.metrics.clone());
+                    }
+                }
+            }
+            for event in &events {
+                let _ = app.emit("process-metrics", event);
+            }
+            self.persist(app, &events);
+            self.thresholds.check(app, manager, &events);
+        }
+
+        /// 保存采样结果，并定期汇总历史数据
+        fn persist(&mut self, app: &AppHandle, events: &[ProcessMetricsEvent]) {
+            let Some(db) = app.try_state::<DbState>() else {
+                return;
+            };
+            let Ok(conn) = db.0.lock() else {
+                return;
+            };
+            if let Err(e) = save_process_metrics(&conn, events) {
+                eprintln!("Failed to save process metrics: {}", e);
+            }
+            let due = self.last_compacted.is_none_or(|last| {
+                last.elapsed() >= Duration::from_secs(super::COMPACT_INTERVAL_SECS)
+            });
+            if due {
+                self.last_compacted = Some(Instant::now());
+                if let Err(e) = compact_process_metrics(&conn, current_timestamp_millis()) {
+                    eprintln!("Failed to compact process metrics: {}", e);
+                }
+            }
+        }
+
+        /// 计算进程树的资源占用，运行实例的第一次采样没有速率
+        fn measure(&mut self, id: &str, pid: u32, tree: &[&ProcStat]) -> ProcessMetrics {
+            let now = Instant::now();
+            let mut rss_bytes = 0;
+            let mut threads = 0;
+            let mut counters = HashMap::new();
+            for stat in tree {
+                let (rss, thread_count) = read_status(stat.pid);
+                rss_bytes += rss;
+                threads += thread_count;
+                let (read_bytes, write_bytes) = read_io(stat.pid);
+                counters.insert(
+                    stat.pid,
+                    Counters {
+                        cpu_ticks: stat.cpu_ticks,
+                        read_bytes,
+                        write_bytes,
+                    },
+                );
+            }
+
+            let mut metrics = ProcessMetrics {
+                sampled_at: current_timestamp_millis(),
+                cpu_percent: 0.0,
+                rss_bytes,
+                threads,
+                processes: tree.len() as u32,
+                read_bytes_per_sec: 0.0,
+                write_bytes_per_sec: 0.0,
+            };
+            if let Some(previous) = self.previous.get(id).filter(|p| p.pid == pid) {
+                let elapsed = now.duration_since(previous.sampled).as_secs_f64();
+                if elapsed > 0.0 {
+                    // 期间启动的子孙进程从 0 开始计算
+                    let mut delta = Counters::default();
+                    for (pid, current) in &counters {
+                        let before = previous.counters.get(pid).copied().unwrap_or_default();
+                        delta.cpu_ticks += current.cpu_ticks.saturating_sub(before.cpu_ticks);
+                        delta.read_bytes += current.read_bytes.saturating_sub(before.read_bytes);
+                        delta.write_bytes += current.write_bytes.saturating_sub(before.write_bytes);
+                    }
+                    metrics.cpu_percent = delta.cpu_ticks as f64 / *CLOCK_TICKS / elapsed * 100.0;
+                    metrics.read_bytes_per_sec = delta.read_bytes as f64 / elapsed;
+                    metrics.write_bytes_per_sec = delta.write_bytes as f64 / elapsed;
+                }
+            }
+
+            self.previous.insert(
+                id.to_string(),
+                Previous {
+                    pid,
+                    sampled: now,
+                    counters,
+                },
+            );
+            metrics
+        }
+    }
+
+    /// 进程及其所有子孙进程（包括同一进程组中父进程已退出的进程）
+    fn process_tree<'a>(
+        pid: u32,
+        stats: &'a [ProcStat],
+        children: &HashMap<u32, Vec<usize>>,
+    ) -> Vec<&'a ProcStat> {
+        let mut visited = vec![false; stats.len()];
+        let mut pending: Vec<usize> = stats
+            .iter()
+            .enumerate()
+            .filter(|(_, stat)| stat.pid == pid || stat.pgrp == pid)
+            .map(|(index, _)| index)
+            .collect();
+        let mut tree = Vec::new();
+        while let Some(index) = pending.pop() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            tree.push(&stats[index]);
+            if let Some(indices) = children.get(&stats[index].pid) {
+                pending.extend(indices);
+            }
+        }
+        tree
+    }
+
+    /// 读取所有进程的 `/proc/<pid>/stat`
+    fn scan_processes() -> Vec<ProcStat> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(read_stat)
+            .collect()
+    }
+
+    fn read_stat(pid: u32) -> Option<ProcStat> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // 进程名可能包含空格和括号，从最后一个 ')' 之后开始解析（第 3 个字段起）
+        let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+        let field = |index: usize| fields.get(index)?.parse::<u64>().ok();
+        Some(ProcStat {
+            pid,
+            ppid: field(1)? as u32,
+            pgrp: field(2)? as u32,
+            cpu_ticks: field(11)? + field(12)?,
+        })
+    }
+
+    /// 读取 `/proc/<pid>/status` 中的常驻内存（字节）和线程数
+    fn read_status(pid: u32) -> (u64, u32) {
+        let Ok(status) = fs::read_to_string(format!("/proc/{}/status", pid)) else {
+            return (0, 0);
+        };
+        let value = |key: &str| {
+            status.lines().find_map(|line| {
+                line.strip_prefix(key)?
+                    .split_whitespace()
+                    .next()?
+                    .parse::<u64>()
+                    .ok()
+            })
+        };
+        let rss_kb = value("VmRSS:").unwrap_or(0);
+        let threads = value("Threads:").unwrap_or(0);
+        (rss_kb * 1024, threads as u32)
+    }
+
+    /// 读取 `/proc/<pid>/io` 中实际读写存储设备的字节数
+    fn read_io(pid: u32) -> (u64, u64) {
+        let Ok(io) = fs::read_to_string(format!("/proc/{}/io", pid)) else {
+            return (0, 0);
+        };
+        let value = |key: &str| {
+            io.lines()
+                .find_map(|line| line.strip_prefix(key)?.trim().parse::<u64>().ok())
+                .unwrap_or(0)
+        };
+        (value("read_bytes:"), value("write_bytes:"))
+    }
+}
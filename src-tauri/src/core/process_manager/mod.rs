@@ -3,26 +3,76 @@
 //! 提供进程启动、停止、监控功能
 //! - Fork 模式：选择可执行文件，以其所在文件夹作为工作目录执行
 //! - 导入模式：选择文件夹，复制到应用工作目录的子目录中执行
-//! - 实时输出监听
+//! - 实时输出监听，可向进程的标准输入发送命令
+//! - 伪终端模式：交互式程序在伪终端中运行（仅 Unix），可调整窗口大小
+//! - 资源限制：内存 / CPU 配额 / 打开文件数 / 进程数（Linux，cgroup v2 或 setrlimit），记录 OOM 退出
+//! - 生命周期事件：统一发送类型化的 `process-event` 事件，Rust 模块也可订阅
+//! - 退出检测：每个子进程由独立线程等待退出，退出后立即回收并发送事件
+//! - 进程守护：按重启策略（no / on-failure / always / unless-stopped）自动重启
+//! - 崩溃循环检测：频繁退出的进程会被隔离，需手动解除
+//! - 优雅停止：先向整个进程树发送停止信号，超时后强制结束
+//! - 退出记录：保存最近一次退出的退出码/信号、时间和原因
+//! - 资源占用：定期采样进程树的 CPU / 内存 / 线程数 / 读写速率（Linux），历史数据按时间降采样保存
+//! - 资源阈值：内存 / CPU 持续超过阈值时发送警告、重启或停止，触发记录归入运行记录
+//! - 健康检查：TCP 连接 / HTTP 请求 / 探测命令，连续失败后可自动重启
+//! - 依赖关系：按依赖顺序启动，按逆序停止，拒绝循环依赖
+//! - 就绪检测：输出匹配 / TCP 端口 / 健康检查，启动超时未就绪视为启动失败
+//! - 生命周期钩子：启动前后、停止前后依次执行的命令，可设置超时和失败处理方式
+//! - 一次性任务：运行至结束，正常退出视为完成，保留最近几次运行的时长和退出码
+//! - 定时任务：按 cron 表达式定时启动 / 停止 / 重启，记录每次触发并处理错过的触发
+//! - 重新接管：应用重启后接管上次启动且仍在运行的进程（校验 PID 和启动时间），避免重复启动
+//! - 退出应用：按依赖逆序分层并行停止，所有进程共享总超时时间，并报告停止进度
+//! - 脱离模式：退出应用时保持进程运行（独立会话，输出写入日志文件），下次启动时重新接管
+//! - 残留实例清理：可选按可执行文件路径或 PID 文件（校验启动时间）结束上次遗留的实例
 
+mod adopt;
 mod config;
+mod crash_loop;
+mod cron;
+mod dependencies;
+mod detach;
+mod events;
+mod exit;
+mod handle;
+mod health;
+mod hooks;
+mod input;
 mod lifecycle;
+mod limits;
+mod metrics;
 mod output;
+mod process_tree;
+mod pty;
 mod query;
+mod readiness;
 mod runner;
+mod scheduler;
+mod shutdown;
+mod stale;
 mod state;
+mod supervisor;
+mod task;
+mod termination;
+#[cfg(target_os = "linux")]
+mod thresholds;
 mod types;
 mod utils;
+mod waiter;
 
 // 导出类型
 pub use types::{
-    CommandType, ProcessConfig, ProcessInfo, ProcessMode, ProcessOutputEvent, ProcessStatus,
+    CommandType, ExitInfo, ExitReason, HealthCheckConfig, HealthProbe, HealthState, HookCommand,
+    HookFailure, MetricsResolution, MissedRunPolicy, ProcessConfig, ProcessEvent,
+    ProcessEventMessage, ProcessExitedEvent, ProcessInfo, ProcessInstance, ProcessKind,
+    ProcessMetrics, ProcessMetricsEvent, ProcessMode, ProcessOptions, ProcessOutputEvent,
+    ProcessQuarantinedEvent, ProcessRun, ProcessSchedule, ProcessStatus, PtySize, QuarantineInfo,
+    ReadinessConfig, ReadinessProbe, ResourceLimits, RestartPolicy, ScheduleAction, ScheduleRun,
+    ScheduleRunStatus, ShutdownProgress, StaleCleanup, StopOutcome, StopResult, StopSignal,
+    ThresholdAction, ThresholdMetric, ThresholdRule, ThresholdTrigger,
 };
 
 // 导出状态管理
-pub use state::{
-    create_process_manager, kill_all_processes, ProcessManager, ProcessManagerState, ProcessOutput,
-};
+pub use state::{create_process_manager, ProcessManager, ProcessManagerState, ProcessOutput};
 
 // 导出配置管理命令
 pub use config::{add_process_fork, add_process_import, remove_process, update_process};
@@ -30,10 +80,41 @@ pub use config::{add_process_fork, add_process_import, remove_process, update_pr
 // 导出生命周期管理命令
 pub use lifecycle::{
     auto_start_processes_on_init, start_auto_start_processes, start_process, stop_process,
+    stop_process_with_dependents,
+};
+
+// 导出崩溃循环隔离命令
+pub use crash_loop::clear_process_quarantine;
+
+// 导出生命周期事件订阅
+pub use events::{subscribe_process_events, ProcessEventBus, ProcessEventHandler};
+
+// 导出进程守护
+pub use supervisor::start_supervisor;
+
+// 导出资源占用采样
+pub use metrics::start_metrics_sampler;
+
+// 导出重新接管
+pub use adopt::{adopt_running_processes, watch_adopted_processes};
+
+// 导出脱离模式
+pub use detach::stop_processes_on_exit;
+
+// 导出退出应用时停止进程
+pub use shutdown::shutdown_processes;
+
+// 导出定时任务
+pub use scheduler::{
+    add_process_schedule, list_process_schedules, list_schedule_runs, remove_process_schedule,
+    start_scheduler, update_process_schedule,
 };
 
 // 导出查询命令
-pub use query::{get_process, list_processes};
+pub use query::{get_process, get_process_metrics, list_process_runs, list_processes};
 
 // 导出输出管理命令
 pub use output::{clear_process_output, get_process_output};
+
+// 导出输入命令
+pub use input::{resize_process_pty, send_process_input};
@@ -0,0 +1,249 @@
+//! 进程树
+//!
+//! 每个托管进程在独立的进程组（Unix）或 Job Object（Windows）中运行，
+//! 停止时向整个进程树发送信号，避免 shell 或启动脚本派生的子进程成为孤儿进程
+
+use std::process::{Child, Command};
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::handle::ProcessHandle;
+use super::types::StopSignal;
+
+/// 托管进程及其所有子孙进程
+pub(crate) struct ProcessTree {
+    #[cfg(unix)]
+    pgid: libc::pid_t,
+    /// 进程组已确认没有存活的进程（Unix）：此后进程组 ID 可能被新进程复用，不再向其发送信号
+    #[cfg(unix)]
+    emptied: AtomicBool,
+    #[cfg(windows)]
+    job: Option<JobHandle>,
+}
+
+/// 配置启动命令，使子进程成为新进程组的组长
+///
+/// 脱离模式下创建新会话（同时成为新进程组的组长），不再受应用所在终端的挂断信号影响
+#[cfg(unix)]
+pub(crate) fn configure_command(cmd: &mut Command, detached: bool) {
+    use std::os::unix::process::CommandExt;
+    if detached {
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    } else {
+        cmd.process_group(0);
+    }
+}
+
+/// 配置启动命令
+///
+/// CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP（停止时向进程组发送 Ctrl-Break）。
+/// Job Object 未设置 KILL_ON_JOB_CLOSE，应用退出后进程本身就会继续运行，脱离模式无需额外处理
+#[cfg(windows)]
+pub(crate) fn configure_command(cmd: &mut Command, _detached: bool) {
+    use std::os::windows::process::CommandExt;
+    cmd.creation_flags(0x08000000 | 0x00000200);
+}
+
+#[cfg(unix)]
+impl ProcessTree {
+    /// 关联已启动的子进程，进程组 ID 即子进程 PID
+    pub fn attach(child: &Child) -> Self {
+        Self::adopt(child.id())
+    }
+
+    /// 关联重新接管的进程（由本应用启动，仍是进程组组长）
+    pub fn adopt(pid: u32) -> Self {
+        Self {
+            pgid: pid as libc::pid_t,
+            emptied: AtomicBool::new(false),
+        }
+    }
+
+    /// 向整个进程组发送停止信号，进程组已没有存活的进程时返回 ESRCH
+    pub fn signal(&self, _child: &mut ProcessHandle, signal: StopSignal) -> std::io::Result<()> {
+        if !self.is_alive() {
+            return Err(std::io::Error::from_raw_os_error(libc::ESRCH));
+        }
+        let sig = match signal {
+            StopSignal::SigTerm => libc::SIGTERM,
+            StopSignal::SigInt => libc::SIGINT,
+            StopSignal::SigHup => libc::SIGHUP,
+            StopSignal::SigQuit => libc::SIGQUIT,
+            StopSignal::SigKill => libc::SIGKILL,
+        };
+        let ret = unsafe { libc::killpg(self.pgid, sig) };
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(std::io::Error::last_os_error())
+        }
+    }
+
+    /// 强制结束整个进程组（进程组已没有存活的进程时不发送）
+    pub fn kill(&self) {
+        if self.is_alive() {
+            unsafe {
+                libc::killpg(self.pgid, libc::SIGKILL);
+            }
+        }
+    }
+
+    /// 进程组中是否仍有存活的进程
+    ///
+    /// 一旦确认进程组为空就不再检查，避免进程组 ID 被复用后误判为存活
+    pub fn is_alive(&self) -> bool {
+        if self.emptied.load(Ordering::Relaxed) {
+            return false;
+        }
+        if unsafe { libc::killpg(self.pgid, 0) } == 0 {
+            return true;
+        }
+        if std::io::Error::last_os_error().raw_os_error() == Some(libc::ESRCH) {
+            self.emptied.store(true, Ordering::Relaxed);
+        }
+        false
+    }
+}
+
+#[cfg(windows)]
+impl ProcessTree {
+    /// 创建 Job Object 并将子进程加入其中
+    ///
+    /// 子进程之后创建的子孙进程自动属于同一个 Job。
+    /// 不设置 KILL_ON_JOB_CLOSE，关闭句柄不会结束进程
+    pub fn attach(child: &Child) -> Self {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::System::JobObjects::{AssignProcessToJobObject, CreateJobObjectW};
+
+        let job = unsafe {
+            let handle = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if handle.is_null() {
+                None
+            } else if AssignProcessToJobObject(handle, child.as_raw_handle() as _) == 0 {
+                windows_sys::Win32::Foundation::CloseHandle(handle);
+                None
+            } else {
+                Some(JobHandle(handle))
+            }
+        };
+        if job.is_none() {
+            eprintln!(
+                "Failed to create job object for process {}: {}",
+                child.id(),
+                std::io::Error::last_os_error()
+            );
+        }
+        Self { job }
+    }
+
+    /// 关联重新接管的进程
+    ///
+    /// 原 Job Object 句柄已随上次运行的应用关闭，无法再次获取，只能结束进程本身
+    pub fn adopt(_pid: u32) -> Self {
+        Self { job: None }
+    }
+
+    /// 向进程组发送停止信号
+    ///
+    /// SIGKILL 直接结束整个 Job，其余信号以 Ctrl-Break 事件发送到进程组
+    pub fn signal(&self, child: &mut ProcessHandle, signal: StopSignal) -> std::io::Result<()> {
+        if signal == StopSignal::SigKill {
+            self.kill();
+            return child.kill();
+        }
+        send_ctrl_break(child.id())
+    }
+
+    /// 强制结束 Job 中的所有进程
+    pub fn kill(&self) {
+        use windows_sys::Win32::System::JobObjects::TerminateJobObject;
+        if let Some(job) = &self.job {
+            unsafe {
+                TerminateJobObject(job.0, 1);
+            }
+        }
+    }
+
+    /// Job 中是否仍有存活的进程
+    pub fn is_alive(&self) -> bool {
+        use windows_sys::Win32::System::JobObjects::{
+            JobObjectBasicAccountingInformation, QueryInformationJobObject,
+            JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+        };
+        let Some(job) = &self.job else {
+            return false;
+        };
+        unsafe {
+            let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = std::mem::zeroed();
+            let ok = QueryInformationJobObject(
+                job.0,
+                JobObjectBasicAccountingInformation,
+                &mut info as *mut _ as *mut _,
+                std::mem::size_of::<JOBOBJECT_BASIC_ACCOUNTING_INFORMATION>() as u32,
+                std::ptr::null_mut(),
+            );
+            ok != 0 && info.ActiveProcesses > 0
+        }
+    }
+}
+
+/// Job Object 句柄，释放时关闭
+#[cfg(windows)]
+struct JobHandle(windows_sys::Win32::Foundation::HANDLE);
+
+// 句柄只在持有 ProcessTree 的线程中使用，可以安全地跨线程移动
+#[cfg(windows)]
+unsafe impl Send for JobHandle {}
+
+#[cfg(windows)]
+impl Drop for JobHandle {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+/// 向子进程所在的控制台发送 Ctrl-Break 事件
+///
+/// 子进程以 CREATE_NEW_PROCESS_GROUP 启动，进程组 ID 即其 PID。
+/// 需要临时附加到子进程的控制台，期间忽略本进程自身收到的 Ctrl 事件
+#[cfg(windows)]
+fn send_ctrl_break(pid: u32) -> std::io::Result<()> {
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler,
+        CTRL_BREAK_EVENT,
+    };
+
+    // 控制台附加状态是进程级的，多个停止操作需要串行执行
+    static CONSOLE_LOCK: Mutex<()> = Mutex::new(());
+    let _guard = CONSOLE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    unsafe {
+        FreeConsole();
+        if AttachConsole(pid) == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        SetConsoleCtrlHandler(None, 1);
+        let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid);
+        let error = std::io::Error::last_os_error();
+        FreeConsole();
+        // 控制台事件异步分发，稍等后再恢复本进程的 Ctrl 处理
+        thread::sleep(Duration::from_millis(50));
+        SetConsoleCtrlHandler(None, 0);
+        if sent == 0 {
+            return Err(error);
+        }
+    }
+    Ok(())
+}
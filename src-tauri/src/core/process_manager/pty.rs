@@ -0,0 +1,150 @@
+//! 伪终端
+//!
+//! 设置了 use_pty 的进程在伪终端中运行：标准输入、输出和错误都连接到伪终端的从设备，
+//! 通过 isatty 判断是否为交互模式、是否输出颜色的程序因此与在终端中运行时表现一致。
+//! 本应用从主设备读取输出（标准输出和标准错误合并为 stdout），发送的输入写入主设备。
+//! 伪终端关闭了回显，发送的输入只以 `stdin` 类型记录一次。仅支持 Unix
+
+use std::fs::File;
+use std::process::Command;
+
+use super::types::PtySize;
+
+/// 伪终端主设备
+#[cfg(unix)]
+pub(crate) struct PtyMaster {
+    file: File,
+}
+
+#[cfg(unix)]
+impl PtyMaster {
+    /// 打开伪终端，并配置启动命令使用其从设备作为标准输入输出和控制终端
+    ///
+    /// 子进程在新会话中运行（同时成为新进程组的组长），不再需要 `configure_command`
+    pub fn open(cmd: &mut Command, size: PtySize) -> Result<Self, String> {
+        use std::os::fd::{FromRawFd, OwnedFd};
+        use std::os::unix::process::CommandExt;
+
+        let mut master: libc::c_int = -1;
+        let mut slave: libc::c_int = -1;
+        let mut winsize = to_winsize(size);
+        let ret = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                // macOS 声明为 *mut，Linux 为 *const
+                std::ptr::addr_of_mut!(winsize),
+            )
+        };
+        if ret != 0 {
+            return Err(format!(
+                "Failed to open pseudo-terminal: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let (master, slave) =
+            unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+        // 子进程只通过复制到 0/1/2 的从设备访问伪终端
+        set_cloexec(&master);
+        set_cloexec(&slave);
+        disable_echo(&slave);
+
+        let clone_slave = |slave: &OwnedFd| {
+            slave
+                .try_clone()
+                .map_err(|e| format!("Failed to open pseudo-terminal: {}", e))
+        };
+        cmd.stdin(clone_slave(&slave)?);
+        cmd.stdout(clone_slave(&slave)?);
+        cmd.stderr(slave);
+        unsafe {
+            cmd.pre_exec(|| {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                // 标准输入已是从设备，将其设为新会话的控制终端
+                if libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        Ok(Self {
+            file: File::from(master),
+        })
+    }
+
+    /// 复制主设备句柄，用于读取输出或写入输入
+    pub fn try_clone(&self) -> std::io::Result<File> {
+        self.file.try_clone()
+    }
+
+    /// 调整窗口大小，前台进程组收到 SIGWINCH
+    pub fn resize(&self, size: PtySize) -> Result<(), String> {
+        use std::os::fd::AsRawFd;
+
+        let winsize = to_winsize(size);
+        let ret = unsafe { libc::ioctl(self.file.as_raw_fd(), libc::TIOCSWINSZ, &winsize) };
+        if ret == -1 {
+            return Err(format!(
+                "Failed to resize pseudo-terminal: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn to_winsize(size: PtySize) -> libc::winsize {
+    libc::winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+#[cfg(unix)]
+fn set_cloexec(fd: &impl std::os::fd::AsRawFd) {
+    unsafe {
+        let flags = libc::fcntl(fd.as_raw_fd(), libc::F_GETFD);
+        if flags != -1 {
+            libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags | libc::FD_CLOEXEC);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn disable_echo(fd: &impl std::os::fd::AsRawFd) {
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd.as_raw_fd(), &mut termios) == 0 {
+            termios.c_lflag &= !libc::ECHO;
+            libc::tcsetattr(fd.as_raw_fd(), libc::TCSANOW, &termios);
+        }
+    }
+}
+
+/// 伪终端主设备（Windows 不支持，无法构造）
+#[cfg(windows)]
+pub(crate) enum PtyMaster {}
+
+#[cfg(windows)]
+impl PtyMaster {
+    pub fn open(_cmd: &mut Command, _size: PtySize) -> Result<Self, String> {
+        Err("PTY mode is only supported on Unix".to_string())
+    }
+
+    pub fn try_clone(&self) -> std::io::Result<File> {
+        match *self {}
+    }
+
+    pub fn resize(&self, _size: PtySize) -> Result<(), String> {
+        match *self {}
+    }
+}
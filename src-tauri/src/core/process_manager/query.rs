@@ -1,99 +1,78 @@
 //! 进程查询命令
 
-use super::state::ProcessManager;
-use super::types::{ProcessInfo, ProcessStatus};
+use super::state::{ProcessManager, ProcessManagerState};
+use super::types::{
+    ExitReason, HealthState, MetricsResolution, ProcessConfig, ProcessInfo, ProcessKind,
+    ProcessMetrics, ProcessRun, ProcessStatus,
+};
+use super::utils::current_timestamp_millis;
+use crate::storage::{load_process_metrics, load_process_runs, DbState};
 
-/// 获取所有进程列表
-#[tauri::command]
-pub fn list_processes(state: tauri::State<ProcessManager>) -> Result<Vec<ProcessInfo>, String> {
-    let mut manager = state.lock().map_err(|e| e.to_string())?;
+/// 根据配置和当前运行状态构造进程信息
+pub(super) fn build_process_info(
+    manager: &ProcessManagerState,
+    config: &ProcessConfig,
+) -> ProcessInfo {
+    let id = &config.id;
+    let runtime = manager.runtime.get(id);
+    let restart_attempts = runtime.map(|r| r.restart_attempts).unwrap_or(0);
+    let next_restart_at = runtime.and_then(|r| r.next_restart_at);
+    let quarantine = runtime.and_then(|r| r.quarantine.clone());
+    let last_exit = runtime.and_then(|r| r.last_exit.clone());
+    let running = manager.processes.contains_key(id);
+    // 健康状态和资源占用只对运行中的进程有意义
+    let (health, health_failures) = match runtime {
+        Some(r) if running => (r.health, r.health_failures),
+        _ => (HealthState::Unknown, 0),
+    };
+    let metrics = runtime.filter(|_| running).and_then(|r| r.metrics.clone());
+    let threshold_triggers = runtime
+        .filter(|_| running)
+        .map(|r| r.threshold_triggers.clone())
+        .unwrap_or_default();
 
-    // 检查已退出的进程
-    let stopped_ids: Vec<String> = manager
+    let adopted = manager
         .processes
-        .iter_mut()
-        .filter_map(|(id, running)| match running.child.try_wait() {
-            Ok(Some(_)) | Err(_) => Some(id.clone()),
-            Ok(None) => None,
-        })
-        .collect();
-
-    for id in stopped_ids {
-        manager.processes.remove(&id);
-    }
-
-    let mut result: Vec<ProcessInfo> = manager
-        .configs
-        .iter()
-        .map(|(id, config)| {
-            let (status, pid, started_at) = if let Some(running) = manager.processes.get(id) {
-                (
-                    ProcessStatus::Running,
-                    Some(running.child.id()),
-                    Some(running.started_at),
-                )
-            } else {
-                (ProcessStatus::Stopped, None, None)
-            };
-
-            let has_output = manager
-                .outputs
-                .get(id)
-                .map(|o| !o.lines.is_empty())
-                .unwrap_or(false);
-
-            ProcessInfo {
-                id: config.id.clone(),
-                name: config.name.clone(),
-                mode: config.mode.clone(),
-                command_type: config.command_type.clone(),
-                command: config.command.clone(),
-                args: config.args.clone(),
-                working_dir: config.working_dir.clone(),
-                source_path: config.source_path.clone(),
-                status,
-                pid,
-                auto_restart: config.auto_restart,
-                auto_start: config.auto_start,
-                started_at,
-                created_at: config.created_at,
-                has_output,
-            }
-        })
-        .collect();
-
-    result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-
-    Ok(result)
-}
-
-/// 获取单个进程信息
-#[tauri::command]
-pub fn get_process(state: tauri::State<ProcessManager>, id: String) -> Result<ProcessInfo, String> {
-    let manager = state.lock().map_err(|e| e.to_string())?;
-
-    let config = manager
-        .configs
-        .get(&id)
-        .ok_or_else(|| "Process not found".to_string())?;
-
-    let (status, pid, started_at) = if let Some(running) = manager.processes.get(&id) {
+        .get(id)
+        .is_some_and(|r| r.child.is_adopted());
+    let detached = manager.processes.get(id).is_some_and(|r| r.detached);
+    let (status, pid, started_at) = if let Some(running) = manager.processes.get(id) {
         (
             ProcessStatus::Running,
             Some(running.child.id()),
             Some(running.started_at),
         )
+    } else if quarantine.is_some() {
+        (ProcessStatus::Error, None, None)
+    } else if next_restart_at.is_some() {
+        (ProcessStatus::Restarting, None, None)
+    } else if last_exit.as_ref().is_some_and(|e| {
+        matches!(
+            e.reason,
+            ExitReason::Crashed
+                | ExitReason::ReadinessTimeout
+                | ExitReason::HookFailed
+                | ExitReason::OomKilled
+        )
+    }) {
+        (ProcessStatus::Error, None, None)
+    } else if config.kind == ProcessKind::Task
+        && last_exit
+            .as_ref()
+            .is_some_and(|e| e.reason == ExitReason::Exited)
+    {
+        (ProcessStatus::Completed, None, None)
     } else {
         (ProcessStatus::Stopped, None, None)
     };
 
     let has_output = manager
         .outputs
-        .get(&id)
+        .get(id)
         .map(|o| !o.lines.is_empty())
         .unwrap_or(false);
 
-    Ok(ProcessInfo {
+    ProcessInfo {
         id: config.id.clone(),
         name: config.name.clone(),
         mode: config.mode.clone(),
@@ -102,12 +81,105 @@ pub fn get_process(state: tauri::State<ProcessManager>, id: String) -> Result<Pr
         args: config.args.clone(),
         working_dir: config.working_dir.clone(),
         source_path: config.source_path.clone(),
+        kind: config.kind,
         status,
         pid,
-        auto_restart: config.auto_restart,
+        adopted,
+        restart_policy: config.restart_policy.clone(),
+        success_exit_codes: config.success_exit_codes.clone(),
         auto_start: config.auto_start,
         started_at,
         created_at: config.created_at,
         has_output,
-    })
+        restart_delay_ms: config.restart_delay_ms,
+        restart_max_delay_ms: config.restart_max_delay_ms,
+        restart_attempts,
+        next_restart_at,
+        crash_loop_max_exits: config.crash_loop_max_exits,
+        crash_loop_window_secs: config.crash_loop_window_secs,
+        quarantine,
+        stop_signal: config.stop_signal,
+        stop_timeout_ms: config.stop_timeout_ms,
+        last_exit,
+        health_check: config.health_check.clone(),
+        health,
+        health_failures,
+        readiness: config.readiness.clone(),
+        depends_on: config.depends_on.clone(),
+        wait_for_dependencies: config.wait_for_dependencies,
+        start_order: config.start_order,
+        start_delay_ms: config.start_delay_ms,
+        stale_cleanup: config.stale_cleanup,
+        survive_app_exit: config.survive_app_exit,
+        detached,
+        pre_start: config.pre_start.clone(),
+        post_start: config.post_start.clone(),
+        pre_stop: config.pre_stop.clone(),
+        post_stop: config.post_stop.clone(),
+        use_pty: config.use_pty,
+        pty_size: config.pty_size,
+        resource_limits: config.resource_limits.clone(),
+        threshold_rules: config.threshold_rules.clone(),
+        task_timeout_ms: config.task_timeout_ms,
+        metrics,
+        threshold_triggers,
+    }
+}
+
+/// 获取所有进程列表
+///
+/// 已退出进程的回收由守护线程负责，这里只读取当前状态
+#[tauri::command]
+pub fn list_processes(state: tauri::State<ProcessManager>) -> Result<Vec<ProcessInfo>, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+
+    let mut result: Vec<ProcessInfo> = manager
+        .configs
+        .values()
+        .map(|config| build_process_info(&manager, config))
+        .collect();
+
+    result.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    Ok(result)
+}
+
+/// 获取单个进程信息
+#[tauri::command]
+pub fn get_process(state: tauri::State<ProcessManager>, id: String) -> Result<ProcessInfo, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+
+    let config = manager
+        .configs
+        .get(&id)
+        .ok_or_else(|| "Process not found".to_string())?;
+
+    Ok(build_process_info(&manager, config))
+}
+
+/// 获取进程最近几次运行的记录（最新的在前），包括运行时长和退出码
+#[tauri::command]
+pub fn list_process_runs(
+    db_state: tauri::State<DbState>,
+    id: String,
+) -> Result<Vec<ProcessRun>, String> {
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    load_process_runs(&conn, &id)
+}
+
+/// 获取进程在时间范围内（毫秒时间戳）的资源占用历史
+///
+/// 未指定粒度时使用仍保留着 `from` 之后数据的最细粒度
+#[tauri::command]
+pub fn get_process_metrics(
+    db_state: tauri::State<DbState>,
+    id: String,
+    from: i64,
+    to: i64,
+    resolution: Option<MetricsResolution>,
+) -> Result<Vec<ProcessMetrics>, String> {
+    let resolution = resolution
+        .unwrap_or_else(|| MetricsResolution::for_range(from, current_timestamp_millis()));
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    load_process_metrics(&conn, &id, resolution, from, to)
 }
@@ -0,0 +1,205 @@
+//! 就绪检测
+//!
+//! 进程启动后按配置的就绪条件（输出匹配、TCP 端口、健康检查）轮询，
+//! 在启动超时内满足条件时发送 ready 事件，超时则视为启动失败并可选地结束进程
+
+use regex::Regex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use super::events::emit_process_event;
+use super::exit::publish_exits;
+use super::health::{probe_tcp, run_probe};
+use super::hooks::{run_post_start_hooks, run_stop_hooks, HookStage};
+use super::state::ProcessManager;
+use super::termination::terminate_instance;
+use super::types::{ExitReason, ProcessConfig, ProcessEvent, ReadinessConfig, ReadinessProbe};
+
+/// 检查就绪条件的轮询间隔
+const READINESS_POLL_INTERVAL_MS: u64 = 100;
+
+/// 单次 TCP 连接尝试的最长时间
+const TCP_ATTEMPT_TIMEOUT_MS: u64 = 1_000;
+
+/// 在后台线程中等待进程就绪，就绪后执行启动后的钩子
+pub(super) fn watch_readiness(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    since_ms: i64,
+    config: ProcessConfig,
+) {
+    let app = app.clone();
+    let manager = manager.clone();
+    let id = id.to_string();
+    thread::spawn(move || {
+        if wait_until_ready(&app, &manager, &id, pid, since_ms, &config).is_ok() {
+            let _ = run_post_start_hooks(&app, &manager, &id, pid, &config);
+        }
+    });
+}
+
+/// 阻塞等待进程满足就绪条件
+///
+/// `since_ms` 为启动时间（毫秒时间戳），只匹配此后产生的输出。
+/// 就绪时发送 ready 事件；进程提前退出或超时未就绪时发送启动失败事件并返回错误
+pub(super) fn wait_until_ready(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    since_ms: i64,
+    config: &ProcessConfig,
+) -> Result<(), String> {
+    // 未设置就绪条件时，启动后即已发送 ready 事件
+    let Some(readiness) = &config.readiness else {
+        return Ok(());
+    };
+
+    let result = poll_readiness(manager, id, pid, since_ms, readiness, config);
+    match &result {
+        Ok(()) => {
+            println!("Process {} is ready", id);
+            mark_ready(app, manager, id, pid);
+        }
+        Err(error) => {
+            eprintln!("Process {} failed to become ready: {}", id, error);
+            emit_process_event(
+                app,
+                id,
+                ProcessEvent::StartFailed {
+                    error: error.clone(),
+                },
+            );
+            if readiness.kill_on_timeout {
+                if let Some(event) =
+                    terminate_instance(app, manager, id, pid, ExitReason::ReadinessTimeout)
+                {
+                    publish_exits(app, std::slice::from_ref(&event));
+                    run_stop_hooks(app, manager, id, HookStage::PostStop);
+                }
+            }
+        }
+    }
+    result
+}
+
+/// 记录运行实例已就绪并发送 ready 事件
+pub(super) fn mark_ready(app: &AppHandle, manager: &ProcessManager, id: &str, pid: u32) {
+    if let Ok(mut state) = manager.lock() {
+        if state.processes.get(id).is_none_or(|r| r.child.id() != pid) {
+            return;
+        }
+        state.runtime.entry(id.to_string()).or_default().ready = true;
+    }
+    emit_process_event(app, id, ProcessEvent::Ready);
+}
+
+/// 等待已启动的进程就绪（用于等待依赖）
+///
+/// 最多等待该进程的启动超时时间，进程未运行或超时未就绪时返回错误
+pub(super) fn wait_for_ready(manager: &ProcessManager, id: &str) -> Result<(), String> {
+    let start = Instant::now();
+    loop {
+        let (running, ready, timeout_ms) = {
+            let state = manager.lock().map_err(|e| e.to_string())?;
+            let timeout_ms = state
+                .configs
+                .get(id)
+                .and_then(|c| c.readiness.as_ref())
+                .map(|r| r.startup_timeout_ms)
+                .unwrap_or(0);
+            let ready = state.runtime.get(id).is_some_and(|r| r.ready);
+            (state.processes.contains_key(id), ready, timeout_ms)
+        };
+        if !running {
+            return Err("Process is not running".to_string());
+        }
+        if ready {
+            return Ok(());
+        }
+        if start.elapsed() >= Duration::from_millis(timeout_ms) {
+            return Err(format!(
+                "Process did not become ready within {} ms",
+                timeout_ms
+            ));
+        }
+        thread::sleep(Duration::from_millis(READINESS_POLL_INTERVAL_MS));
+    }
+}
+
+/// 轮询就绪条件直到满足、进程退出或超时
+fn poll_readiness(
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    since_ms: i64,
+    readiness: &ReadinessConfig,
+    config: &ProcessConfig,
+) -> Result<(), String> {
+    let pattern = match &readiness.probe {
+        ReadinessProbe::OutputMatch { pattern } => {
+            Some(Regex::new(pattern).map_err(|e| format!("Invalid readiness pattern: {}", e))?)
+        }
+        _ => None,
+    };
+
+    let start = Instant::now();
+    let timeout = Duration::from_millis(readiness.startup_timeout_ms);
+    loop {
+        if !is_running(manager, id, pid) {
+            return Err("Process exited before becoming ready".to_string());
+        }
+
+        let ready = match &readiness.probe {
+            ReadinessProbe::OutputMatch { .. } => pattern
+                .as_ref()
+                .is_some_and(|re| output_matches(manager, id, since_ms, re)),
+            ReadinessProbe::Tcp { host, port } => {
+                let remaining = timeout.saturating_sub(start.elapsed());
+                let attempt = remaining
+                    .min(Duration::from_millis(TCP_ATTEMPT_TIMEOUT_MS))
+                    .max(Duration::from_millis(1));
+                probe_tcp(host, *port, attempt).is_ok()
+            }
+            ReadinessProbe::HealthCheck => match &config.health_check {
+                Some(check) => run_probe(check, config).is_ok(),
+                None => return Err("Readiness requires a health check".to_string()),
+            },
+        };
+        if ready {
+            return Ok(());
+        }
+
+        if start.elapsed() >= timeout {
+            return Err(format!(
+                "Process did not become ready within {} ms",
+                readiness.startup_timeout_ms
+            ));
+        }
+        thread::sleep(Duration::from_millis(READINESS_POLL_INTERVAL_MS));
+    }
+}
+
+/// 进程是否仍是同一个运行实例
+fn is_running(manager: &ProcessManager, id: &str, pid: u32) -> bool {
+    manager
+        .lock()
+        .map(|state| state.processes.get(id).is_some_and(|r| r.child.id() == pid))
+        .unwrap_or(false)
+}
+
+/// 启动后的输出中是否有匹配的行
+fn output_matches(manager: &ProcessManager, id: &str, since_ms: i64, re: &Regex) -> bool {
+    let Ok(state) = manager.lock() else {
+        return false;
+    };
+    state.outputs.get(id).is_some_and(|output| {
+        output
+            .lines
+            .iter()
+            .any(|(timestamp, _, line)| *timestamp >= since_ms && re.is_match(line))
+    })
+}
@@ -2,16 +2,25 @@
 
 use regex::Regex;
 use std::collections::HashMap;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Read};
 use std::process::{Child, Command, Stdio};
-use std::sync::LazyLock;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 
-use super::state::{ProcessManager, RunningProcess};
-use super::types::{CommandType, ProcessConfig, ProcessOutputEvent};
-use super::utils::{current_timestamp, current_timestamp_millis, kill_processes_by_name};
+use super::detach::LogFiles;
+use super::handle::ProcessHandle;
+use super::limits::{apply_resource_limits, Cgroup};
+use super::process_tree::{configure_command, ProcessTree};
+use super::pty::PtyMaster;
+use super::state::{ProcessInput, ProcessManager, RunningProcess};
+use super::types::{CommandType, HealthState, ProcessConfig, ProcessOutputEvent};
+use super::utils::{current_timestamp, current_timestamp_millis};
+
+/// 伪终端输出中没有换行的内容在空闲该时长后输出
+const PTY_FLUSH_IDLE_MS: u64 = 100;
 
 /// 用于匹配 ANSI 转义序列的正则表达式
 /// 使用 LazyLock 缓存编译后的正则表达式以提高性能
@@ -160,11 +169,24 @@ fn get_full_user_environment() -> HashMap<String, String> {
 /// 进程启动结果
 pub struct SpawnResult {
     pub child: Child,
+    pub(crate) tree: ProcessTree,
     pub started_at: i64,
+    /// 是否以脱离模式启动（输出写入日志文件）
+    pub detached: bool,
+    /// 在伪终端中运行时的主设备
+    pub(crate) pty: Option<PtyMaster>,
+    /// 设置了资源限制时进程树所在的子 cgroup
+    pub(crate) cgroup: Option<Cgroup>,
 }
 
 /// 启动进程的核心逻辑
-pub fn spawn_process(config: &ProcessConfig) -> Result<SpawnResult, String> {
+///
+/// 提供 `log_files` 时以脱离模式启动：在独立会话中运行，输出写入日志文件而不是管道。
+/// 否则设置了 use_pty 时在伪终端中运行
+pub fn spawn_process(
+    config: &ProcessConfig,
+    log_files: Option<&LogFiles>,
+) -> Result<SpawnResult, String> {
     // 检查 command 是否为空
     if config.command.is_empty() {
         return Err(
@@ -174,16 +196,6 @@ pub fn spawn_process(config: &ProcessConfig) -> Result<SpawnResult, String> {
 
     let mut cmd = match config.command_type {
         CommandType::Executable => {
-            // 启动前结束同名进程（仅对可执行文件）
-            if let Some(exe_name) = Path::new(&config.command).file_name() {
-                let exe_name_str = exe_name.to_string_lossy();
-                if let Ok(count) = kill_processes_by_name(&exe_name_str) {
-                    if count > 0 {
-                        thread::sleep(std::time::Duration::from_millis(500));
-                    }
-                }
-            }
-
             let mut cmd = Command::new(&config.command);
             cmd.args(&config.args);
             cmd
@@ -226,22 +238,46 @@ pub fn spawn_process(config: &ProcessConfig) -> Result<SpawnResult, String> {
         cmd.env(key, value);
     }
 
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
+    let mut pty = None;
+    match log_files {
+        Some(files) => {
+            let (stdout, stderr) = files.create()?;
+            cmd.stdin(Stdio::null());
+            cmd.stdout(stdout);
+            cmd.stderr(stderr);
+        }
+        // 伪终端模式下子进程在新会话中运行，同样是新进程组的组长
+        None if config.use_pty => {
+            pty = Some(PtyMaster::open(&mut cmd, config.pty_size)?);
+        }
+        None => {
+            cmd.stdin(Stdio::piped());
+            cmd.stdout(Stdio::piped());
+            cmd.stderr(Stdio::piped());
+        }
+    }
 
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        cmd.creation_flags(0x08000000);
+    // 在独立的进程组（脱离模式下为独立会话）中启动，停止时结束整个进程树
+    if pty.is_none() {
+        configure_command(&mut cmd, log_files.is_some());
     }
+    let cgroup = apply_resource_limits(&mut cmd, &config.id, &config.resource_limits)?;
 
     let child = cmd
         .spawn()
         .map_err(|e| format!("Failed to start process: {}", e))?;
 
+    let tree = ProcessTree::attach(&child);
     let started_at = current_timestamp();
 
-    Ok(SpawnResult { child, started_at })
+    Ok(SpawnResult {
+        child,
+        tree,
+        started_at,
+        detached: log_files.is_some(),
+        pty,
+        cgroup,
+    })
 }
 
 /// 设置进程输出监听线程
@@ -249,8 +285,24 @@ pub fn setup_output_listeners(
     app: &AppHandle,
     manager: &ProcessManager,
     id: &str,
-    child: &mut Child,
+    spawned: &mut SpawnResult,
 ) {
+    // 伪终端主设备监听线程
+    if let Some(pty) = &spawned.pty {
+        match pty.try_clone() {
+            Ok(master) => {
+                let app_clone = app.clone();
+                let manager_clone = manager.clone();
+                let id_clone = id.to_string();
+                thread::spawn(move || {
+                    read_pty_output(&app_clone, &manager_clone, &id_clone, master);
+                });
+            }
+            Err(e) => eprintln!("Failed to read pseudo-terminal of process {}: {}", id, e),
+        }
+    }
+
+    let child = &mut spawned.child;
     // stdout 监听线程
     if let Some(stdout) = child.stdout.take() {
         let app_clone = app.clone();
@@ -259,23 +311,7 @@ pub fn setup_output_listeners(
         thread::spawn(move || {
             let reader = BufReader::new(stdout);
             for line in reader.lines().map_while(Result::ok) {
-                // 清理 ANSI 转义序列
-                let clean_line = strip_ansi_escape_codes(&line);
-                let timestamp = current_timestamp_millis();
-                if let Ok(mut state) = manager_clone.lock() {
-                    if let Some(output) = state.outputs.get_mut(&id_clone) {
-                        output.push_line(timestamp, "stdout".to_string(), clean_line.clone());
-                    }
-                }
-                let _ = app_clone.emit(
-                    "process-output",
-                    ProcessOutputEvent {
-                        id: id_clone.clone(),
-                        output_type: "stdout".to_string(),
-                        line: clean_line,
-                        timestamp,
-                    },
-                );
+                push_output_line(&app_clone, &manager_clone, &id_clone, "stdout", &line);
             }
         });
     }
@@ -288,42 +324,172 @@ pub fn setup_output_listeners(
         thread::spawn(move || {
             let reader = BufReader::new(stderr);
             for line in reader.lines().map_while(Result::ok) {
-                // 清理 ANSI 转义序列
-                let clean_line = strip_ansi_escape_codes(&line);
-                let timestamp = current_timestamp_millis();
-                if let Ok(mut state) = manager_clone.lock() {
-                    if let Some(output) = state.outputs.get_mut(&id_clone) {
-                        output.push_line(timestamp, "stderr".to_string(), clean_line.clone());
+                push_output_line(&app_clone, &manager_clone, &id_clone, "stderr", &line);
+            }
+        });
+    }
+}
+
+/// 读取伪终端输出，进程树中所有进程关闭从设备后读取失败（EIO）即结束
+///
+/// 完整的行立即输出；没有换行的内容（提示符、`\r` 刷新的进度条等）在空闲
+/// `PTY_FLUSH_IDLE_MS` 后输出，不等待换行
+fn read_pty_output(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    mut master: impl Read + Send + 'static,
+) {
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match master.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
                     }
                 }
-                let _ = app_clone.emit(
-                    "process-output",
-                    ProcessOutputEvent {
-                        id: id_clone.clone(),
-                        output_type: "stderr".to_string(),
-                        line: clean_line,
-                        timestamp,
-                    },
-                );
             }
-        });
+        }
+    });
+
+    let mut pending: Vec<u8> = Vec::new();
+    loop {
+        match rx.recv_timeout(Duration::from_millis(PTY_FLUSH_IDLE_MS)) {
+            Ok(chunk) => {
+                pending.extend_from_slice(&chunk);
+                while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = pending.drain(..=pos).collect();
+                    push_output_line(app, manager, id, "stdout", &terminal_line(&line));
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if !pending.is_empty() {
+                    let line = std::mem::take(&mut pending);
+                    push_output_line(app, manager, id, "stdout", &terminal_line(&line));
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                if !pending.is_empty() {
+                    push_output_line(app, manager, id, "stdout", &terminal_line(&pending));
+                }
+                break;
+            }
+        }
+    }
+}
+
+/// 将伪终端输出的一行转换为显示的文本
+///
+/// 换行为 \r\n；行内的 `\r` 使光标回到行首，只保留最后一次覆盖的内容（如进度条）
+fn terminal_line(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_end_matches(['\n', '\r']);
+    match text.rfind('\r') {
+        Some(pos) => text[pos + 1..].to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// 记录一行输出并发送输出事件
+pub(super) fn push_output_line(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    output_type: &str,
+    line: &str,
+) {
+    // 清理 ANSI 转义序列
+    let clean_line = strip_ansi_escape_codes(line);
+    let timestamp = current_timestamp_millis();
+    if let Ok(mut state) = manager.lock() {
+        if let Some(output) = state.outputs.get_mut(id) {
+            output.push_line(timestamp, output_type.to_string(), clean_line.clone());
+        }
     }
+    let _ = app.emit(
+        "process-output",
+        ProcessOutputEvent {
+            id: id.to_string(),
+            output_type: output_type.to_string(),
+            line: clean_line,
+            timestamp,
+        },
+    );
 }
 
 /// 注册运行中的进程到状态管理器
+///
+/// 已有运行中的实例时不覆盖（否则原实例成为无人管理的孤儿进程），正在退出应用时也不再注册
+/// （退出流程不会再停止它），两种情况下都结束新启动的进程树并返回错误
 pub fn register_running_process(
     manager: &ProcessManager,
     id: &str,
-    child: Child,
+    spawned: SpawnResult,
     config: ProcessConfig,
-    started_at: i64,
 ) -> Result<(), String> {
+    let mut child = spawned.child;
+    // 伪终端模式下输入写入主设备
+    let stdin: Option<ProcessInput> = match &spawned.pty {
+        Some(pty) => pty
+            .try_clone()
+            .ok()
+            .map(|master| Arc::new(Mutex::new(master)) as ProcessInput),
+        None => child
+            .stdin
+            .take()
+            .map(|stdin| Arc::new(Mutex::new(stdin)) as ProcessInput),
+    };
     let mut state = manager.lock().map_err(|e| e.to_string())?;
+    if state.processes.contains_key(id) || state.shutting_down {
+        let error = if state.shutting_down {
+            "Application is shutting down"
+        } else {
+            "Process is already running"
+        };
+        drop(state);
+        spawned.tree.kill();
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(error.to_string());
+    }
     let running = RunningProcess {
-        child,
+        child: ProcessHandle::Spawned(child),
+        tree: spawned.tree,
         config,
-        started_at,
+        started_at: spawned.started_at,
+        launched_at: Instant::now(),
+        detached: spawned.detached,
+        stdin,
+        pty: spawned.pty,
+        cgroup: spawned.cgroup,
     };
     state.processes.insert(id.to_string(), running);
+    // 新的运行实例尚未完成健康检查
+    let runtime = state.runtime.entry(id.to_string()).or_default();
+    runtime.health = HealthState::Unknown;
+    runtime.health_failures = 0;
+    runtime.ready = false;
+    runtime.metrics = None;
+    runtime.threshold_triggers.clear();
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminal_line_strips_line_endings() {
+        assert_eq!(terminal_line(b"hello\r\n"), "hello");
+        assert_eq!(terminal_line(b"prompt> "), "prompt> ");
+    }
+
+    #[test]
+    fn terminal_line_keeps_text_after_carriage_return() {
+        assert_eq!(terminal_line(b"10%\r50%\r"), "50%");
+        assert_eq!(terminal_line(b"10%\r50%\r\n"), "50%");
+    }
+}
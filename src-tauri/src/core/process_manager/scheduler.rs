@@ -0,0 +1,393 @@
+//! 定时任务
+//!
+//! 按 cron 表达式定时启动、停止或重启进程，定时任务和每次触发的记录保存在数据库中。
+//! 应用未运行或系统休眠期间错过的触发时间按错过策略补执行一次或记录为已跳过
+
+use chrono::{Local, TimeZone};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::cron::CronSchedule;
+use super::lifecycle::{start_with_dependencies, stop_process_with_manager};
+use super::state::ProcessManager;
+use super::types::{
+    MissedRunPolicy, ProcessSchedule, ScheduleAction, ScheduleRun, ScheduleRunStatus,
+};
+use super::utils::current_timestamp;
+use crate::storage::{
+    delete_process_schedule, load_process_schedules, load_schedule_runs, save_process_schedule,
+    save_schedule_run, set_process_user_stopped, set_schedule_checked_at, DbState,
+};
+
+/// 调度线程检查时间的间隔
+const SCHEDULER_POLL_INTERVAL_MS: u64 = 1_000;
+
+/// 默认返回的触发记录数量
+const DEFAULT_RUNS_LIMIT: u32 = 50;
+
+/// 一次待执行的触发
+struct PendingRun {
+    schedule: ProcessSchedule,
+    scheduled_at: i64,
+    missed: bool,
+}
+
+/// 启动调度线程
+///
+/// 启动后立即检查一次，处理应用未运行期间错过的触发时间，之后每分钟检查一次
+pub fn start_scheduler(app: &AppHandle, manager: &ProcessManager) {
+    let app = app.clone();
+    let manager = manager.clone();
+    thread::spawn(move || {
+        let mut last_minute = None;
+        loop {
+            let minute = current_minute();
+            if last_minute != Some(minute) {
+                last_minute = Some(minute);
+                check_schedules(&app, &manager, minute);
+            }
+            thread::sleep(Duration::from_millis(SCHEDULER_POLL_INTERVAL_MS));
+        }
+    });
+}
+
+/// 检查所有定时任务在 `minute`（整分钟时间戳）之前是否有未处理的触发时间
+///
+/// 正在退出应用时不处理，错过的触发留到下次启动时按错过策略处理
+fn check_schedules(app: &AppHandle, manager: &ProcessManager, minute: i64) {
+    if manager.lock().map_or(true, |state| state.shutting_down) {
+        return;
+    }
+    let Some(db) = app.try_state::<DbState>() else {
+        return;
+    };
+    let schedules = match db.0.lock() {
+        Ok(conn) => match load_process_schedules(&conn) {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                eprintln!("Failed to load schedules: {}", e);
+                return;
+            }
+        },
+        Err(_) => return,
+    };
+
+    let mut pending = Vec::new();
+    for schedule in schedules {
+        if !schedule.enabled || schedule.last_checked_at >= minute {
+            continue;
+        }
+        let cron = match CronSchedule::parse(&schedule.cron) {
+            Ok(cron) => cron,
+            Err(e) => {
+                eprintln!("Invalid schedule {}: {}", schedule.id, e);
+                continue;
+            }
+        };
+
+        let first_due = cron
+            .next_after(schedule.last_checked_at)
+            .filter(|at| *at <= minute);
+        if let Some(first_due) = first_due {
+            let due_now = first_due == minute
+                || Local
+                    .timestamp_opt(minute, 0)
+                    .single()
+                    .is_some_and(|time| cron.matches(&time));
+            if due_now {
+                // 本次触发同时覆盖之前错过的触发
+                if first_due < minute && schedule.missed_run_policy == MissedRunPolicy::Skip {
+                    record_skipped_run(app, &schedule, first_due);
+                }
+                pending.push(PendingRun {
+                    schedule: schedule.clone(),
+                    scheduled_at: minute,
+                    missed: false,
+                });
+            } else {
+                match schedule.missed_run_policy {
+                    MissedRunPolicy::RunOnce => pending.push(PendingRun {
+                        schedule: schedule.clone(),
+                        scheduled_at: first_due,
+                        missed: true,
+                    }),
+                    MissedRunPolicy::Skip => record_skipped_run(app, &schedule, first_due),
+                }
+            }
+        }
+
+        if let Ok(conn) = db.0.lock() {
+            if let Err(e) = set_schedule_checked_at(&conn, &schedule.id, minute) {
+                eprintln!("Failed to update schedule {}: {}", schedule.id, e);
+            }
+        }
+    }
+
+    if pending.is_empty() {
+        return;
+    }
+
+    // 停止和重启可能需要等待进程退出，在后台线程中按顺序执行
+    let app = app.clone();
+    let manager = manager.clone();
+    thread::spawn(move || {
+        for run in pending {
+            fire(&app, &manager, run);
+        }
+    });
+}
+
+/// 执行一次触发并记录结果
+fn fire(app: &AppHandle, manager: &ProcessManager, run: PendingRun) {
+    let schedule = &run.schedule;
+    let (status, message) = match run_action(app, manager, &schedule.process_id, schedule.action) {
+        Ok(None) => (ScheduleRunStatus::Success, None),
+        Ok(Some(reason)) => (ScheduleRunStatus::Skipped, Some(reason)),
+        Err(e) => (ScheduleRunStatus::Failed, Some(e)),
+    };
+    println!(
+        "Schedule {} fired {} for process {}: {:?}",
+        schedule.id,
+        schedule.action.as_str(),
+        schedule.process_id,
+        status
+    );
+    save_run(
+        app,
+        ScheduleRun {
+            id: 0,
+            schedule_id: schedule.id.clone(),
+            process_id: schedule.process_id.clone(),
+            action: schedule.action,
+            scheduled_at: run.scheduled_at,
+            fired_at: current_timestamp(),
+            missed: run.missed,
+            status,
+            message,
+        },
+    );
+}
+
+/// 通过生命周期函数执行定时操作
+///
+/// 进程已处于目标状态时不执行，返回跳过的原因
+fn run_action(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    action: ScheduleAction,
+) -> Result<Option<String>, String> {
+    let (running, pending_restart) = {
+        let state = manager.lock().map_err(|e| e.to_string())?;
+        if !state.configs.contains_key(id) {
+            return Err("Process config not found".to_string());
+        }
+        let pending_restart = state
+            .runtime
+            .get(id)
+            .is_some_and(|r| r.next_restart_at.is_some());
+        (state.processes.contains_key(id), pending_restart)
+    };
+
+    match action {
+        ScheduleAction::Start => {
+            if running {
+                return Ok(Some("Process is already running".to_string()));
+            }
+            start(app, manager, id)?;
+        }
+        ScheduleAction::Stop => {
+            if !running && !pending_restart {
+                return Ok(Some("Process is not running".to_string()));
+            }
+            stop_process_with_manager(app, manager, id)?;
+            // 与手动停止一致，unless-stopped 策略的进程在应用下次启动时不再启动
+            set_user_stopped(app, &[id.to_string()], true);
+        }
+        ScheduleAction::Restart => {
+            if running || pending_restart {
+                stop_process_with_manager(app, manager, id)?;
+            }
+            start(app, manager, id)?;
+        }
+    }
+    Ok(None)
+}
+
+/// 启动进程及其依赖，并清除用户停止标记
+fn start(app: &AppHandle, manager: &ProcessManager, id: &str) -> Result<(), String> {
+    let (started, _) = start_with_dependencies(app, manager, id)?;
+    set_user_stopped(app, &started, false);
+    Ok(())
+}
+
+fn set_user_stopped(app: &AppHandle, ids: &[String], stopped: bool) {
+    if let Some(db) = app.try_state::<DbState>() {
+        if let Ok(conn) = db.0.lock() {
+            for id in ids {
+                let _ = set_process_user_stopped(&conn, id, stopped);
+            }
+        }
+    }
+}
+
+/// 记录按错过策略跳过的触发
+fn record_skipped_run(app: &AppHandle, schedule: &ProcessSchedule, scheduled_at: i64) {
+    save_run(
+        app,
+        ScheduleRun {
+            id: 0,
+            schedule_id: schedule.id.clone(),
+            process_id: schedule.process_id.clone(),
+            action: schedule.action,
+            scheduled_at,
+            fired_at: current_timestamp(),
+            missed: true,
+            status: ScheduleRunStatus::Skipped,
+            message: Some("Missed scheduled time".to_string()),
+        },
+    );
+}
+
+fn save_run(app: &AppHandle, run: ScheduleRun) {
+    if let Some(db) = app.try_state::<DbState>() {
+        if let Ok(conn) = db.0.lock() {
+            if let Err(e) = save_schedule_run(&conn, &run) {
+                eprintln!("Failed to record schedule run: {}", e);
+            }
+        }
+    }
+}
+
+/// 当前时间所在的整分钟时间戳
+fn current_minute() -> i64 {
+    let now = current_timestamp();
+    now - now.rem_euclid(60)
+}
+
+/// 计算下一次触发时间（禁用或表达式无效时为 None）
+fn with_next_run(mut schedule: ProcessSchedule) -> ProcessSchedule {
+    schedule.next_run_at = if schedule.enabled {
+        CronSchedule::parse(&schedule.cron)
+            .ok()
+            .and_then(|cron| cron.next_after(schedule.last_checked_at.max(current_timestamp())))
+    } else {
+        None
+    };
+    schedule
+}
+
+/// 校验定时任务的进程和 cron 表达式
+fn validate_schedule(manager: &ProcessManager, process_id: &str, cron: &str) -> Result<(), String> {
+    let state = manager.lock().map_err(|e| e.to_string())?;
+    if !state.configs.contains_key(process_id) {
+        return Err("Process not found".to_string());
+    }
+    CronSchedule::parse(cron)?;
+    Ok(())
+}
+
+/// 获取定时任务列表，可按进程筛选
+#[tauri::command]
+pub fn list_process_schedules(
+    db_state: tauri::State<DbState>,
+    process_id: Option<String>,
+) -> Result<Vec<ProcessSchedule>, String> {
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    let schedules = load_process_schedules(&conn)?;
+    Ok(schedules
+        .into_iter()
+        .filter(|s| process_id.as_ref().is_none_or(|id| s.process_id == *id))
+        .map(with_next_run)
+        .collect())
+}
+
+/// 添加定时任务
+#[tauri::command]
+pub fn add_process_schedule(
+    state: tauri::State<ProcessManager>,
+    db_state: tauri::State<DbState>,
+    process_id: String,
+    cron: String,
+    action: ScheduleAction,
+    enabled: Option<bool>,
+    missed_run_policy: Option<MissedRunPolicy>,
+) -> Result<ProcessSchedule, String> {
+    let cron = cron.trim().to_string();
+    validate_schedule(state.inner(), &process_id, &cron)?;
+
+    let schedule = ProcessSchedule {
+        id: uuid::Uuid::new_v4().to_string(),
+        process_id,
+        cron,
+        action,
+        enabled: enabled.unwrap_or(true),
+        missed_run_policy: missed_run_policy.unwrap_or_default(),
+        // 从当前分钟开始计算，之前的触发时间不算错过
+        last_checked_at: current_minute(),
+        next_run_at: None,
+        created_at: current_timestamp(),
+    };
+
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    save_process_schedule(&conn, &schedule)?;
+    Ok(with_next_run(schedule))
+}
+
+/// 修改定时任务
+#[tauri::command]
+pub fn update_process_schedule(
+    state: tauri::State<ProcessManager>,
+    db_state: tauri::State<DbState>,
+    id: String,
+    cron: String,
+    action: ScheduleAction,
+    enabled: bool,
+    missed_run_policy: MissedRunPolicy,
+) -> Result<ProcessSchedule, String> {
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    let mut schedule = load_process_schedules(&conn)?
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| "Schedule not found".to_string())?;
+
+    let cron = cron.trim().to_string();
+    validate_schedule(state.inner(), &schedule.process_id, &cron)?;
+
+    // 重新启用或修改表达式时从当前分钟开始计算，禁用期间的触发时间不算错过
+    if (enabled && !schedule.enabled) || cron != schedule.cron {
+        schedule.last_checked_at = current_minute();
+    }
+    schedule.cron = cron;
+    schedule.action = action;
+    schedule.enabled = enabled;
+    schedule.missed_run_policy = missed_run_policy;
+
+    save_process_schedule(&conn, &schedule)?;
+    Ok(with_next_run(schedule))
+}
+
+/// 删除定时任务及其触发记录
+#[tauri::command]
+pub fn remove_process_schedule(db_state: tauri::State<DbState>, id: String) -> Result<(), String> {
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    delete_process_schedule(&conn, &id)
+}
+
+/// 获取定时任务的触发记录（最新的在前），可按进程或定时任务筛选
+#[tauri::command]
+pub fn list_schedule_runs(
+    db_state: tauri::State<DbState>,
+    process_id: Option<String>,
+    schedule_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<ScheduleRun>, String> {
+    let conn = db_state.0.lock().map_err(|e| e.to_string())?;
+    load_schedule_runs(
+        &conn,
+        process_id.as_deref(),
+        schedule_id.as_deref(),
+        limit.unwrap_or(DEFAULT_RUNS_LIMIT),
+    )
+}
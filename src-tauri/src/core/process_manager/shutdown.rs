@@ -0,0 +1,136 @@
+//! 退出应用时停止进程
+//!
+//! 按依赖关系的逆序分层停止（依赖它的进程先停止），同一层内的进程并行停止，
+//! 每个进程使用各自的停止信号和超时时间，停止前后执行其钩子。所有进程共享一个总超时时间，
+//! 超时后剩余的进程被立即强制结束。停止过程中通过 `shutdown-progress` 事件报告进度。
+//! 开始停止后不再启动、自动重启或按定时任务启动进程
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::dependencies::shutdown_layers;
+use super::exit::{publish_exits, record_exit};
+use super::hooks::{run_hooks_parallel, HookStage};
+use super::state::{ProcessManager, RunningProcess};
+use super::termination::terminate_all;
+use super::types::{ProcessConfig, ShutdownProgress};
+use crate::storage::{load_shutdown_timeout_secs, DbState, DEFAULT_SHUTDOWN_TIMEOUT_SECS};
+
+/// 停止进度跟踪
+struct Progress {
+    total: usize,
+    /// 尚未停止的进程 ID 和名称
+    pending: HashMap<String, String>,
+    started: Instant,
+    deadline_ms: u64,
+}
+
+impl Progress {
+    fn snapshot(&self, done: bool) -> ShutdownProgress {
+        let mut pending: Vec<String> = self.pending.values().cloned().collect();
+        pending.sort();
+        ShutdownProgress {
+            total: self.total,
+            stopped: self.total - self.pending.len(),
+            pending,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            deadline_ms: self.deadline_ms,
+            done,
+        }
+    }
+}
+
+/// 退出应用时停止所有运行中的进程
+///
+/// 超时未退出的进程树被强制结束，退出原因记录为应用退出。
+/// 每有进程停止时发送 `shutdown-progress` 事件并调用 `on_progress`（如更新托盘提示）
+pub fn shutdown_processes(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    on_progress: impl Fn(&ShutdownProgress),
+) {
+    let timeout_secs = app
+        .try_state::<DbState>()
+        .and_then(|db| {
+            db.0.lock()
+                .ok()
+                .map(|conn| load_shutdown_timeout_secs(&conn))
+        })
+        .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS);
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(timeout_secs);
+
+    let (layers, pending) = match manager.lock() {
+        Ok(mut state) => {
+            // 停止期间守护线程和定时任务不再启动新进程，否则已停止的层中会出现无人管理的进程
+            state.begin_shutdown();
+            let ids: Vec<String> = state.processes.keys().cloned().collect();
+            let pending: HashMap<String, String> = state
+                .processes
+                .iter()
+                .map(|(id, running)| (id.clone(), running.config.name.clone()))
+                .collect();
+            (shutdown_layers(&state.configs, &ids), pending)
+        }
+        Err(_) => return,
+    };
+    if pending.is_empty() {
+        return;
+    }
+
+    let mut progress = Progress {
+        total: pending.len(),
+        pending,
+        started,
+        deadline_ms: timeout_secs * 1000,
+    };
+    let report = |progress: &ShutdownProgress| {
+        let _ = app.emit("shutdown-progress", progress);
+        on_progress(progress);
+    };
+    report(&progress.snapshot(false));
+
+    for layer in layers {
+        let mut processes: Vec<(String, RunningProcess)> = Vec::new();
+        let mut configs: Vec<ProcessConfig> = Vec::new();
+        match manager.lock() {
+            Ok(mut state) => {
+                for id in layer {
+                    match state.processes.remove(&id) {
+                        Some(running) => {
+                            let config = state.configs.get(&id).unwrap_or(&running.config);
+                            configs.push(config.clone());
+                            processes.push((id, running));
+                        }
+                        // 已在此期间自行退出
+                        None => {
+                            progress.pending.remove(&id);
+                        }
+                    }
+                }
+            }
+            Err(_) => return,
+        }
+        run_hooks_parallel(app, manager, &configs, HookStage::PreStop, deadline);
+        let exits = terminate_all(processes, deadline, |event| {
+            progress.pending.remove(&event.id);
+            report(&progress.snapshot(false));
+        });
+
+        if let Ok(mut state) = manager.lock() {
+            for event in &exits {
+                record_exit(&mut state, &event.id, &event.exit);
+            }
+        }
+        publish_exits(app, &exits);
+        run_hooks_parallel(app, manager, &configs, HookStage::PostStop, deadline);
+    }
+
+    report(&progress.snapshot(true));
+    println!(
+        "Stopped {} processes in {} ms",
+        progress.total,
+        started.elapsed().as_millis()
+    );
+}
@@ -0,0 +1,417 @@
+//! 残留实例清理
+//!
+//! 应用异常退出后，上次启动的子进程可能仍在运行并占用端口或文件。
+//! 进程可选择在启动前清理残留实例（默认不清理）：
+//! - exe-path：结束可执行文件路径与本进程完全一致的进程
+//!   （Linux 扫描 `/proc/*/exe`，Windows 比较进程映像的完整路径）
+//! - pid-file：启动后将 PID 和进程启动时间写入 PID 文件，下次启动前只结束该 PID，
+//!   并先确认其启动时间与记录一致，避免误杀复用了该 PID 的其他进程
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::handle::process_start_time;
+use super::state::ProcessManager;
+use super::types::{ProcessConfig, StaleCleanup};
+
+/// 等待残留进程退出的轮询间隔
+#[cfg(unix)]
+const STALE_EXIT_POLL_INTERVAL_MS: u64 = 50;
+
+/// 启动前按配置清理残留实例
+///
+/// 当前由本应用管理的进程不会被结束。清理失败时只记录日志，不阻止启动
+pub(super) fn cleanup_stale_instances(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    config: &ProcessConfig,
+) {
+    if config.stale_cleanup == StaleCleanup::None {
+        return;
+    }
+    let managed: HashSet<u32> = manager
+        .lock()
+        .map(|state| state.processes.values().map(|r| r.child.id()).collect())
+        .unwrap_or_default();
+
+    let timeout = Duration::from_millis(config.stop_timeout_ms);
+    let result = match config.stale_cleanup {
+        StaleCleanup::None => Ok(Vec::new()),
+        StaleCleanup::ExePath => resolve_executable(config).and_then(|exe| find_by_exe(&exe)),
+        StaleCleanup::PidFile => find_by_pid_file(app, id),
+    };
+    match result {
+        Ok(pids) => {
+            let stale: Vec<u32> = pids
+                .into_iter()
+                .filter(|pid| !is_managed(*pid, &managed))
+                .collect();
+            for pid in &stale {
+                terminate_pid(*pid, timeout);
+            }
+            if !stale.is_empty() {
+                println!("Terminated stale instances of process {}: {:?}", id, stale);
+            }
+        }
+        Err(e) => eprintln!(
+            "Failed to clean up stale instances of process {}: {}",
+            id, e
+        ),
+    }
+}
+
+/// 记录运行实例的 PID 和启动时间（pid-file 模式）
+pub(super) fn write_pid_file(app: &AppHandle, id: &str, pid: u32) {
+    let result = pid_file_path(app, id).and_then(|path| {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = match process_start_time(pid) {
+            Some(start_time) => format!("{} {}\n", pid, start_time),
+            None => format!("{}\n", pid),
+        };
+        fs::write(&path, content).map_err(|e| e.to_string())
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to write PID file for process {}: {}", id, e);
+    }
+}
+
+/// 运行实例退出后删除其 PID 文件（文件中记录的是其他实例时保留）
+pub(super) fn release_pid_file(app: &AppHandle, id: &str, pid: u32) {
+    let Ok(path) = pid_file_path(app, id) else {
+        return;
+    };
+    let recorded = fs::read_to_string(&path)
+        .ok()
+        .and_then(|content| parse_pid_file(&content));
+    if recorded.is_some_and(|(recorded_pid, _)| recorded_pid == pid) {
+        let _ = fs::remove_file(&path);
+    }
+}
+
+/// 删除进程的 PID 文件
+pub(super) fn remove_pid_file(app: &AppHandle, id: &str) {
+    if let Ok(path) = pid_file_path(app, id) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn pid_file_path(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    let app_data = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?;
+    Ok(app_data.join("pids").join(format!("{}.pid", id)))
+}
+
+/// 解析 PID 文件：`<pid> [<启动时间>]`
+fn parse_pid_file(content: &str) -> Option<(u32, Option<u64>)> {
+    let mut parts = content.split_whitespace();
+    let pid = parts.next()?.parse().ok()?;
+    let start_time = parts.next().and_then(|s| s.parse().ok());
+    Some((pid, start_time))
+}
+
+/// 读取 PID 文件中记录的进程，启动时间与记录一致时才视为残留实例
+fn find_by_pid_file(app: &AppHandle, id: &str) -> Result<Vec<u32>, String> {
+    let path = pid_file_path(app, id)?;
+    let Ok(content) = fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    let _ = fs::remove_file(&path);
+    verify_pid_file(&content)
+}
+
+/// 核对 PID 文件记录的进程：仍在运行且启动时间与记录一致时返回其 PID
+fn verify_pid_file(content: &str) -> Result<Vec<u32>, String> {
+    let Some((pid, recorded)) = parse_pid_file(content) else {
+        return Ok(Vec::new());
+    };
+    match (recorded, process_start_time(pid)) {
+        (Some(recorded), Some(current)) if recorded == current => Ok(vec![pid]),
+        // 进程已退出或 PID 已被其他进程复用
+        (Some(_), _) => Ok(Vec::new()),
+        (None, _) => Err(format!(
+            "Cannot verify the start time of PID {}, not terminating it",
+            pid
+        )),
+    }
+}
+
+/// 解析可执行文件的完整路径（与启动时的查找规则一致）
+fn resolve_executable(config: &ProcessConfig) -> Result<PathBuf, String> {
+    let command = Path::new(&config.command);
+    let working_dir = Path::new(&config.working_dir);
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if command.is_absolute() {
+        candidates.push(command.to_path_buf());
+    } else if command.components().count() > 1 {
+        candidates.push(working_dir.join(command));
+    } else {
+        if cfg!(windows) {
+            candidates.push(working_dir.join(command));
+        }
+        if let Some(paths) = std::env::var_os("PATH") {
+            candidates.extend(std::env::split_paths(&paths).map(|dir| dir.join(command)));
+        }
+    }
+    if cfg!(windows) && command.extension().is_none() {
+        let with_exe: Vec<PathBuf> = candidates
+            .iter()
+            .map(|path| path.with_extension("exe"))
+            .collect();
+        candidates.extend(with_exe);
+    }
+
+    candidates
+        .iter()
+        .filter(|path| path.is_file())
+        .find_map(|path| fs::canonicalize(path).ok())
+        .map(|path| strip_verbatim_prefix(&path))
+        .ok_or_else(|| format!("Executable not found: {}", config.command))
+}
+
+/// 去掉 Windows 规范化路径的 `\\?\` 前缀
+fn strip_verbatim_prefix(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    match text.strip_prefix(r"\\?\") {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path.to_path_buf(),
+    }
+}
+
+/// 是否为本应用当前管理的进程（或其进程组中的进程）
+fn is_managed(pid: u32, managed: &HashSet<u32>) -> bool {
+    if managed.contains(&pid) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        let pgid = unsafe { libc::getpgid(pid as libc::pid_t) };
+        if pgid > 0 && managed.contains(&(pgid as u32)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// 扫描 `/proc/*/exe`，查找可执行文件路径完全一致的进程
+#[cfg(target_os = "linux")]
+fn find_by_exe(exe: &Path) -> Result<Vec<u32>, String> {
+    let own_pid = std::process::id();
+    let entries = fs::read_dir("/proc").map_err(|e| format!("Failed to read /proc: {}", e))?;
+    let mut pids = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == own_pid {
+            continue;
+        }
+        // 其他用户的进程没有读取权限，直接跳过
+        let Ok(target) = fs::read_link(entry.path().join("exe")) else {
+            continue;
+        };
+        // 可执行文件被替换或删除后，链接目标带有 " (deleted)" 后缀
+        let target = target.to_string_lossy();
+        let target = target.strip_suffix(" (deleted)").unwrap_or(&target);
+        if Path::new(target) == exe {
+            pids.push(pid);
+        }
+    }
+    Ok(pids)
+}
+
+/// 其他 Unix 平台没有 `/proc/*/exe`，不支持按路径清理
+#[cfg(all(unix, not(target_os = "linux")))]
+fn find_by_exe(_exe: &Path) -> Result<Vec<u32>, String> {
+    Err("Stale cleanup by executable path is only supported on Linux and Windows".to_string())
+}
+
+/// 遍历进程快照，查找映像路径完全一致的进程
+#[cfg(windows)]
+fn find_by_exe(exe: &Path) -> Result<Vec<u32>, String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let own_pid = std::process::id();
+    let target = exe.to_string_lossy().to_lowercase();
+    let mut pids = Vec::new();
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot.is_null() {
+            return Err("Failed to create process snapshot".to_string());
+        }
+
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        if Process32FirstW(snapshot, &mut entry) != 0 {
+            loop {
+                let pid = entry.th32ProcessID;
+                if pid != own_pid
+                    && image_path(pid)
+                        .is_some_and(|path| path.to_string_lossy().to_lowercase() == target)
+                {
+                    pids.push(pid);
+                }
+                if Process32NextW(snapshot, &mut entry) == 0 {
+                    break;
+                }
+            }
+        }
+        CloseHandle(snapshot);
+    }
+    Ok(pids)
+}
+
+/// 进程映像的完整路径
+#[cfg(windows)]
+fn image_path(pid: u32) -> Option<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(handle, 0, buf.as_mut_ptr(), &mut len);
+        CloseHandle(handle);
+        if ok == 0 {
+            return None;
+        }
+        Some(PathBuf::from(OsString::from_wide(&buf[..len as usize])))
+    }
+}
+
+/// 结束残留进程：先发送 SIGTERM，超时后强制结束
+///
+/// 本应用启动的进程是进程组组长，此时结束整个进程组
+#[cfg(unix)]
+fn terminate_pid(pid: u32, timeout: Duration) {
+    use std::thread;
+    use std::time::Instant;
+
+    let pid = pid as libc::pid_t;
+    let is_group_leader = unsafe { libc::getpgid(pid) } == pid;
+    let send = |sig: libc::c_int| unsafe {
+        if is_group_leader {
+            libc::killpg(pid, sig);
+        } else {
+            libc::kill(pid, sig);
+        }
+    };
+
+    send(libc::SIGTERM);
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if unsafe { libc::kill(pid, 0) } != 0 {
+            return;
+        }
+        thread::sleep(Duration::from_millis(STALE_EXIT_POLL_INTERVAL_MS));
+    }
+    send(libc::SIGKILL);
+}
+
+/// 结束残留进程并等待其退出
+#[cfg(windows)]
+fn terminate_pid(pid: u32, timeout: Duration) {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, TerminateProcess, WaitForSingleObject, PROCESS_SYNCHRONIZE, PROCESS_TERMINATE,
+    };
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE | PROCESS_SYNCHRONIZE, 0, pid);
+        if handle.is_null() {
+            return;
+        }
+        if TerminateProcess(handle, 1) != 0 {
+            WaitForSingleObject(handle, timeout.as_millis() as u32);
+        }
+        CloseHandle(handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pid_file() {
+        assert_eq!(parse_pid_file("1234 5678\n"), Some((1234, Some(5678))));
+        assert_eq!(parse_pid_file("1234\n"), Some((1234, None)));
+        assert_eq!(parse_pid_file("1234 bogus"), Some((1234, None)));
+        assert_eq!(parse_pid_file(""), None);
+        assert_eq!(parse_pid_file("not-a-pid 5678"), None);
+    }
+
+    #[cfg(any(target_os = "linux", windows))]
+    #[test]
+    fn pid_file_matches_only_same_start_time() {
+        let pid = std::process::id();
+        let start_time = process_start_time(pid).expect("start time of own process");
+        assert_eq!(
+            verify_pid_file(&format!("{} {}\n", pid, start_time)),
+            Ok(vec![pid])
+        );
+        // PID 被复用：启动时间不一致
+        assert_eq!(
+            verify_pid_file(&format!("{} {}\n", pid, start_time + 1)),
+            Ok(Vec::new())
+        );
+        // 没有记录启动时间时无法确认，不结束该进程
+        assert!(verify_pid_file(&format!("{}\n", pid)).is_err());
+        assert_eq!(verify_pid_file("garbage"), Ok(Vec::new()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn exited_process_is_not_stale() {
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let pid = child.id();
+        let start_time = process_start_time(pid).unwrap_or(1);
+        child.wait().unwrap();
+        assert_eq!(
+            verify_pid_file(&format!("{} {}\n", pid, start_time)),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn strips_verbatim_prefix() {
+        assert_eq!(
+            strip_verbatim_prefix(Path::new(r"\\?\C:\app\server.exe")),
+            PathBuf::from(r"C:\app\server.exe")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(Path::new("/usr/bin/server")),
+            PathBuf::from("/usr/bin/server")
+        );
+    }
+
+    #[test]
+    fn managed_processes_are_skipped() {
+        let own = std::process::id();
+        assert!(is_managed(own, &HashSet::from([own])));
+        assert!(!is_managed(own, &HashSet::new()));
+    }
+}
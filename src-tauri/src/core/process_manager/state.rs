@@ -1,22 +1,77 @@
 //! 进程管理器状态
 
-use std::collections::HashMap;
-use std::process::Child;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
-use super::types::ProcessConfig;
+use super::handle::ProcessHandle;
+use super::limits::Cgroup;
+use super::process_tree::ProcessTree;
+use super::pty::PtyMaster;
+use super::types::{
+    ExitInfo, HealthState, ProcessConfig, ProcessMetrics, QuarantineInfo, ThresholdTrigger,
+};
 
 /// 输出缓冲限制常量
 pub const MAX_OUTPUT_LINES: usize = 1000;
 pub const MAX_LINE_LENGTH: usize = 4096; // 单行最大 4KB
 pub const MAX_TOTAL_OUTPUT_BYTES: usize = 2 * 1024 * 1024; // 每个进程最大 2MB 输出
 
+/// 进程的输入：标准输入管道或伪终端主设备
+pub(crate) type ProcessInput = Arc<Mutex<dyn Write + Send>>;
+
 /// 运行中的进程句柄
 pub(crate) struct RunningProcess {
-    pub child: Child,
-    #[allow(dead_code)]
+    /// 本实例启动的子进程或重新接管的进程
+    pub child: ProcessHandle,
+    /// 子进程所在的进程组 / Job Object
+    pub tree: ProcessTree,
     pub config: ProcessConfig,
     pub started_at: i64,
+    /// 启动时刻（单调时钟），用于计算运行时长
+    pub launched_at: Instant,
+    /// 以脱离模式运行，退出应用时保持运行
+    pub detached: bool,
+    /// 子进程的标准输入（脱离模式和重新接管的进程没有）
+    pub stdin: Option<ProcessInput>,
+    /// 在伪终端中运行时的主设备，用于调整窗口大小
+    pub pty: Option<PtyMaster>,
+    /// 设置了资源限制时进程树所在的子 cgroup
+    pub cgroup: Option<Cgroup>,
+}
+
+/// 进程运行时附加状态（不随进程退出而清除）
+#[derive(Default)]
+pub(crate) struct ProcessRuntime {
+    /// 连续自动重启次数
+    pub restart_attempts: u32,
+    /// 下一次自动重启的时间（毫秒时间戳），None 表示没有待执行的重启
+    pub next_restart_at: Option<i64>,
+    /// 最近的退出时间（秒），用于崩溃循环检测
+    pub recent_exits: VecDeque<i64>,
+    /// 崩溃循环隔离信息，存在时不再自动重启
+    pub quarantine: Option<QuarantineInfo>,
+    /// 最近一次退出的信息
+    pub last_exit: Option<ExitInfo>,
+    /// 当前运行实例的健康状态
+    pub health: HealthState,
+    /// 连续健康检查失败次数
+    pub health_failures: u32,
+    /// 当前运行实例是否已就绪
+    pub ready: bool,
+    /// 当前运行实例最近一次采样的资源占用
+    pub metrics: Option<ProcessMetrics>,
+    /// 当前运行实例中触发的资源阈值规则（按时间顺序）
+    pub threshold_triggers: Vec<ThresholdTrigger>,
+}
+
+impl ProcessRuntime {
+    /// 取消待执行的重启并重置退避计数，返回是否存在待执行的重启
+    pub fn reset_restart(&mut self) -> bool {
+        self.restart_attempts = 0;
+        self.next_restart_at.take().is_some()
+    }
 }
 
 /// 进程输出缓冲
@@ -68,6 +123,11 @@ pub struct ProcessManagerState {
     pub(crate) processes: HashMap<String, RunningProcess>,
     pub(crate) configs: HashMap<String, ProcessConfig>,
     pub(crate) outputs: HashMap<String, ProcessOutput>,
+    pub(crate) runtime: HashMap<String, ProcessRuntime>,
+    /// 正在启动（已通过“是否已在运行”检查、尚未注册）的进程
+    pub(crate) starting: HashSet<String>,
+    /// 正在退出应用：不再启动、自动重启或按定时任务启动进程
+    pub(crate) shutting_down: bool,
 }
 
 impl ProcessManagerState {
@@ -76,6 +136,17 @@ impl ProcessManagerState {
             processes: HashMap::new(),
             configs: HashMap::new(),
             outputs: HashMap::new(),
+            runtime: HashMap::new(),
+            starting: HashSet::new(),
+            shutting_down: false,
+        }
+    }
+
+    /// 开始退出应用：禁止之后的启动并取消所有待执行的自动重启
+    pub(crate) fn begin_shutdown(&mut self) {
+        self.shutting_down = true;
+        for runtime in self.runtime.values_mut() {
+            runtime.next_restart_at = None;
         }
     }
 }
@@ -92,18 +163,3 @@ pub type ProcessManager = Arc<Mutex<ProcessManagerState>>;
 pub fn create_process_manager() -> ProcessManager {
     Arc::new(Mutex::new(ProcessManagerState::new()))
 }
-
-/// 终止所有运行中的子进程
-pub fn kill_all_processes(manager: &ProcessManager) {
-    if let Ok(mut state) = manager.lock() {
-        for (id, mut running) in state.processes.drain() {
-            if let Err(e) = running.child.kill() {
-                eprintln!("Failed to kill process {}: {}", id, e);
-            } else {
-                println!("Killed process: {}", id);
-            }
-            // 等待进程完全退出
-            let _ = running.child.wait();
-        }
-    }
-}
@@ -0,0 +1,426 @@
+//! 进程守护
+//!
+//! 子进程退出时回收进程并记录退出信息，根据进程的重启策略按指数退避安排重启，
+//! 健康检查连续失败、资源占用超过阈值的进程也经由同一流程重启，后台线程定期执行到期的重启
+
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+use tauri::AppHandle;
+
+use super::crash_loop;
+use super::events::emit_process_event;
+use super::exit::{classify_exit, exit_info, publish_exits, record_exit};
+use super::handle::ExitPoll;
+use super::hooks::{run_stop_hooks, HookStage};
+use super::lifecycle::restart_process_with_manager;
+use super::state::{ProcessManager, ProcessManagerState};
+use super::termination::terminate_instance;
+use super::types::{
+    ExitReason, ProcessConfig, ProcessEvent, ProcessExitedEvent, ProcessKind, RestartPolicy,
+};
+use super::utils::{current_timestamp, current_timestamp_millis};
+
+/// 守护线程检查重启的间隔
+const SUPERVISOR_INTERVAL_MS: u64 = 500;
+
+/// 进程运行超过该时长后退出，视为稳定运行过，重置退避计数
+const STABLE_RUN_SECS: i64 = 10;
+
+static SUPERVISOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 启动进程守护线程
+pub fn start_supervisor(app: &AppHandle, manager: &ProcessManager) {
+    // 防止重复启动守护线程
+    if SUPERVISOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    let manager = manager.clone();
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_millis(SUPERVISOR_INTERVAL_MS));
+        supervise_once(&app, &manager);
+    });
+}
+
+/// 计算第 attempt 次（从 0 开始）重启前的退避延迟
+pub(super) fn backoff_delay_ms(base_ms: u64, max_ms: u64, attempt: u32) -> u64 {
+    let factor = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    base_ms.saturating_mul(factor).min(max_ms)
+}
+
+/// 判断退出状态是否为正常退出
+///
+/// 退出码为 0 或在 `success_exit_codes` 中视为正常退出；
+/// 被信号终止或无法获取退出状态视为异常退出
+pub(super) fn is_success_exit(config: &ProcessConfig, status: Option<&ExitStatus>) -> bool {
+    match status.and_then(|s| s.code()) {
+        Some(code) => code == 0 || config.success_exit_codes.contains(&code),
+        None => false,
+    }
+}
+
+/// 根据重启策略判断进程退出后是否需要重启
+///
+/// `attempts` 为已经连续执行过的重启次数。任务正常退出即已完成，不会被重启
+pub(super) fn should_restart(
+    config: &ProcessConfig,
+    status: Option<&ExitStatus>,
+    attempts: u32,
+) -> bool {
+    if config.kind == ProcessKind::Task && is_success_exit(config, status) {
+        return false;
+    }
+    match &config.restart_policy {
+        RestartPolicy::No => false,
+        RestartPolicy::Always | RestartPolicy::UnlessStopped => true,
+        RestartPolicy::OnFailure { max_retries } => {
+            !is_success_exit(config, status) && max_retries.is_none_or(|max| attempts < max)
+        }
+    }
+}
+
+/// 为进程安排下一次自动重启，返回重启事件
+///
+/// 检测到崩溃循环时不再安排重启，返回隔离事件；正在退出应用时不安排重启
+fn schedule_restart(state: &mut ProcessManagerState, id: &str) -> Option<ProcessEvent> {
+    if state.shutting_down {
+        return None;
+    }
+    let config = state.configs.get(id)?;
+    let (base_ms, max_ms) = (config.restart_delay_ms, config.restart_max_delay_ms);
+
+    let runtime = state.runtime.entry(id.to_string()).or_default();
+    if let Some(event) = crash_loop::record_exit(runtime, config, current_timestamp()) {
+        eprintln!("Process {} quarantined: {}", id, event.reason);
+        return Some(event.into());
+    }
+
+    let delay = backoff_delay_ms(base_ms, max_ms, runtime.restart_attempts);
+    runtime.restart_attempts = runtime.restart_attempts.saturating_add(1);
+    runtime.next_restart_at = Some(current_timestamp_millis() + delay as i64);
+
+    println!(
+        "Process {} exited, restarting in {} ms (attempt {})",
+        id, delay, runtime.restart_attempts
+    );
+    Some(ProcessEvent::Restarting {
+        attempt: runtime.restart_attempts,
+        delay_ms: delay,
+    })
+}
+
+/// 处理子进程退出（由退出等待线程调用）
+///
+/// 回收进程、记录退出信息，根据重启策略安排重启，再执行停止后的钩子。
+/// `pid` 用于确认退出的仍是当前登记的进程，已被停止流程回收时直接返回
+pub(super) fn handle_exit(app: &AppHandle, manager: &ProcessManager, id: &str, pid: u32) {
+    let event: ProcessExitedEvent;
+    let restart: Option<ProcessEvent>;
+    {
+        let Ok(mut state) = manager.lock() else {
+            return;
+        };
+
+        let status = match state.processes.get_mut(id) {
+            Some(running) if running.child.id() == pid => match running.child.try_wait() {
+                Ok(ExitPoll::Exited(status)) => status,
+                Ok(ExitPoll::Running) => return,
+                Err(_) => None,
+            },
+            _ => return,
+        };
+        let Some(running) = state.processes.remove(id) else {
+            return;
+        };
+        let Some(config) = state.configs.get(id).cloned() else {
+            return;
+        };
+
+        let info = exit_info(
+            status.as_ref(),
+            classify_exit(&config, status.as_ref(), running.cgroup.as_ref()),
+            running.launched_at,
+        );
+        record_exit(&mut state, id, &info);
+        event = ProcessExitedEvent {
+            id: id.to_string(),
+            pid,
+            exit: info,
+        };
+
+        if current_timestamp() - running.started_at >= STABLE_RUN_SECS {
+            if let Some(runtime) = state.runtime.get_mut(id) {
+                runtime.restart_attempts = 0;
+            }
+        }
+
+        let attempts = state
+            .runtime
+            .get(id)
+            .map(|r| r.restart_attempts)
+            .unwrap_or(0);
+        restart = if should_restart(&config, status.as_ref(), attempts) {
+            schedule_restart(&mut state, id)
+        } else {
+            None
+        };
+    }
+
+    publish_exits(app, std::slice::from_ref(&event));
+    if let Some(restart) = restart {
+        emit_process_event(app, id, restart);
+    }
+    run_stop_hooks(app, manager, id, HookStage::PostStop);
+}
+
+/// 结束运行中的实例并重启（健康检查连续失败、资源占用超过阈值时调用）
+///
+/// 按停止配置结束进程树，记录退出原因为 `reason`，再通过退避重启流程重新启动。
+/// 重启不受重启策略限制，但仍会触发崩溃循环隔离
+pub(super) fn restart_instance(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    reason: ExitReason,
+) {
+    let Some(event) = terminate_instance(app, manager, id, pid, reason) else {
+        return;
+    };
+    let restart = manager
+        .lock()
+        .ok()
+        .and_then(|mut state| schedule_restart(&mut state, id));
+
+    publish_exits(app, std::slice::from_ref(&event));
+    if let Some(restart) = restart {
+        emit_process_event(app, id, restart);
+    }
+    run_stop_hooks(app, manager, id, HookStage::PostStop);
+}
+
+/// 执行一轮检查：执行到期的重启
+fn supervise_once(app: &AppHandle, manager: &ProcessManager) {
+    let mut due: Vec<String> = Vec::new();
+    {
+        let Ok(mut state) = manager.lock() else {
+            return;
+        };
+        if state.shutting_down {
+            return;
+        }
+
+        let now_ms = current_timestamp_millis();
+        for (id, runtime) in state.runtime.iter_mut() {
+            if runtime.next_restart_at.is_some_and(|at| at <= now_ms) {
+                runtime.next_restart_at = None;
+                due.push(id.clone());
+            }
+        }
+    }
+
+    for id in due {
+        match restart_process_with_manager(app, manager, &id) {
+            Ok(()) => {
+                println!("Auto-restarted process: {}", id);
+            }
+            Err(e) => {
+                eprintln!("Failed to auto-restart process {}: {}", id, e);
+                // 启动失败时继续按退避策略重试
+                let restart = manager.lock().ok().and_then(|mut state| {
+                    let attempts = state
+                        .runtime
+                        .get(&id)
+                        .map(|r| r.restart_attempts)
+                        .unwrap_or(0);
+                    let retry = state
+                        .configs
+                        .get(&id)
+                        .is_some_and(|c| should_restart(c, None, attempts));
+                    if retry {
+                        schedule_restart(&mut state, &id)
+                    } else {
+                        None
+                    }
+                });
+                if let Some(restart) = restart {
+                    emit_process_event(app, &id, restart);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以指定退出码退出的状态
+    fn exited(code: i32) -> ExitStatus {
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            ExitStatus::from_raw(code << 8)
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::ExitStatusExt;
+            ExitStatus::from_raw(code as u32)
+        }
+    }
+
+    fn config(restart_policy: RestartPolicy) -> ProcessConfig {
+        ProcessConfig {
+            id: "p".into(),
+            restart_policy,
+            success_exit_codes: vec![3],
+            restart_delay_ms: 100,
+            restart_max_delay_ms: 1000,
+            crash_loop_max_exits: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let delays: Vec<u64> = (0..6).map(|n| backoff_delay_ms(100, 1000, n)).collect();
+        assert_eq!(delays, [100, 200, 400, 800, 1000, 1000]);
+        // 次数很大时不溢出
+        assert_eq!(backoff_delay_ms(100, 1000, 64), 1000);
+        assert_eq!(backoff_delay_ms(u64::MAX, u64::MAX, 3), u64::MAX);
+    }
+
+    #[test]
+    fn success_exit_codes() {
+        let config = config(RestartPolicy::No);
+        assert!(is_success_exit(&config, Some(&exited(0))));
+        assert!(is_success_exit(&config, Some(&exited(3))));
+        assert!(!is_success_exit(&config, Some(&exited(1))));
+        assert!(!is_success_exit(&config, None));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn killed_by_signal_is_a_failure() {
+        use std::os::unix::process::ExitStatusExt;
+        let killed = ExitStatus::from_raw(libc::SIGKILL);
+        let config = config(RestartPolicy::OnFailure { max_retries: None });
+        assert!(!is_success_exit(&config, Some(&killed)));
+        assert!(should_restart(&config, Some(&killed), 0));
+    }
+
+    #[test]
+    fn restart_policy_no() {
+        let config = config(RestartPolicy::No);
+        assert!(!should_restart(&config, Some(&exited(1)), 0));
+        assert!(!should_restart(&config, None, 0));
+    }
+
+    #[test]
+    fn restart_policy_on_failure() {
+        let config = config(RestartPolicy::OnFailure {
+            max_retries: Some(2),
+        });
+        assert!(should_restart(&config, Some(&exited(1)), 0));
+        assert!(should_restart(&config, Some(&exited(1)), 1));
+        assert!(!should_restart(&config, Some(&exited(1)), 2));
+        assert!(!should_restart(&config, Some(&exited(0)), 0));
+        assert!(!should_restart(&config, Some(&exited(3)), 0));
+
+        let unlimited = ProcessConfig {
+            restart_policy: RestartPolicy::OnFailure { max_retries: None },
+            ..config
+        };
+        assert!(should_restart(&unlimited, Some(&exited(1)), 1000));
+    }
+
+    #[test]
+    fn restart_policy_always_and_unless_stopped() {
+        for policy in [RestartPolicy::Always, RestartPolicy::UnlessStopped] {
+            let config = config(policy);
+            assert!(should_restart(&config, Some(&exited(0)), 0));
+            assert!(should_restart(&config, Some(&exited(3)), 0));
+            assert!(should_restart(&config, Some(&exited(1)), 1000));
+        }
+    }
+
+    #[test]
+    fn completed_task_is_not_restarted() {
+        let config = ProcessConfig {
+            kind: ProcessKind::Task,
+            ..config(RestartPolicy::Always)
+        };
+        assert!(!should_restart(&config, Some(&exited(0)), 0));
+        assert!(!should_restart(&config, Some(&exited(3)), 0));
+        assert!(should_restart(&config, Some(&exited(1)), 0));
+    }
+
+    #[test]
+    fn schedule_restart_backs_off() {
+        let mut state = ProcessManagerState::new();
+        state
+            .configs
+            .insert("p".into(), config(RestartPolicy::Always));
+
+        let delays: Vec<u64> = (0..5)
+            .map(|_| match schedule_restart(&mut state, "p") {
+                Some(ProcessEvent::Restarting { delay_ms, .. }) => delay_ms,
+                other => panic!("unexpected event: {:?}", other),
+            })
+            .collect();
+        assert_eq!(delays, [100, 200, 400, 800, 1000]);
+        let runtime = &state.runtime["p"];
+        assert_eq!(runtime.restart_attempts, 5);
+        assert!(runtime.next_restart_at.is_some());
+    }
+
+    #[test]
+    fn schedule_restart_quarantines_crash_loop() {
+        let mut state = ProcessManagerState::new();
+        let config = ProcessConfig {
+            crash_loop_max_exits: 2,
+            crash_loop_window_secs: 60,
+            ..config(RestartPolicy::Always)
+        };
+        state.configs.insert("p".into(), config);
+
+        assert!(matches!(
+            schedule_restart(&mut state, "p"),
+            Some(ProcessEvent::Restarting { .. })
+        ));
+        assert!(matches!(
+            schedule_restart(&mut state, "p"),
+            Some(ProcessEvent::Restarting { .. })
+        ));
+        assert!(matches!(
+            schedule_restart(&mut state, "p"),
+            Some(ProcessEvent::Quarantined { exits: 3, .. })
+        ));
+        let runtime = &state.runtime["p"];
+        assert!(runtime.quarantine.is_some());
+        assert!(runtime.next_restart_at.is_none());
+    }
+
+    #[test]
+    fn schedule_restart_skipped_during_shutdown() {
+        let mut state = ProcessManagerState::new();
+        state
+            .configs
+            .insert("p".into(), config(RestartPolicy::Always));
+        state.begin_shutdown();
+        assert!(schedule_restart(&mut state, "p").is_none());
+        assert!(state
+            .runtime
+            .get("p")
+            .is_none_or(|r| r.next_restart_at.is_none()));
+    }
+
+    #[test]
+    fn schedule_restart_unknown_process() {
+        let mut state = ProcessManagerState::new();
+        assert!(schedule_restart(&mut state, "missing").is_none());
+    }
+}
@@ -0,0 +1,46 @@
+//! 一次性任务
+//!
+//! 任务类型的进程运行至结束，正常退出视为成功完成而不是停止。
+//! 依赖任务并设置了等待依赖的进程，在任务成功完成后才会启动，
+//! 任务超过 `task_timeout_ms` 仍未结束时启动失败。
+//! 上次运行已成功完成的任务不会随依赖它的进程再次运行，需要手动启动才会重新运行
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::state::ProcessManager;
+use super::types::ExitReason;
+
+/// 检查任务是否结束的轮询间隔
+const TASK_POLL_INTERVAL_MS: u64 = 100;
+
+/// 等待任务运行结束（包括正在启动和按重启策略进行的重试），成功完成时返回 Ok
+///
+/// 最多等待任务的 `task_timeout_ms`，超时后返回错误（任务本身继续运行）
+pub(super) fn wait_for_task(manager: &ProcessManager, id: &str) -> Result<(), String> {
+    let start = Instant::now();
+    loop {
+        {
+            let state = manager.lock().map_err(|e| e.to_string())?;
+            let timeout_ms = state
+                .configs
+                .get(id)
+                .map(|c| c.task_timeout_ms)
+                .unwrap_or(0);
+            let runtime = state.runtime.get(id);
+            let pending_restart = runtime.is_some_and(|r| r.next_restart_at.is_some());
+            let running = state.processes.contains_key(id) || state.starting.contains(id);
+            if !running && !pending_restart {
+                return match runtime.and_then(|r| r.last_exit.as_ref()) {
+                    Some(exit) if exit.reason == ExitReason::Exited => Ok(()),
+                    Some(exit) => Err(format!("Task failed: {}", exit.reason.as_str())),
+                    None => Err("Task has not run".to_string()),
+                };
+            }
+            if start.elapsed() >= Duration::from_millis(timeout_ms) {
+                return Err(format!("Task did not finish within {} ms", timeout_ms));
+            }
+        }
+        thread::sleep(Duration::from_millis(TASK_POLL_INTERVAL_MS));
+    }
+}
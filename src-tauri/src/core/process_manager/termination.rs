@@ -0,0 +1,242 @@
+//! 进程终止
+//!
+//! 停止进程时先向整个进程树发送配置的停止信号（Unix 为 SIGTERM 等信号，Windows 为 Ctrl-Break），
+//! 在超时时间内等待进程树自行退出，超时后再强制结束
+
+use std::process::ExitStatus;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+
+use super::events::emit_process_event;
+use super::exit::{classify_exit, exit_info, record_exit};
+use super::handle::{ExitPoll, ProcessHandle};
+use super::hooks::{run_stop_hooks, HookStage};
+use super::process_tree::ProcessTree;
+use super::state::{ProcessManager, RunningProcess};
+use super::types::{ExitReason, ProcessEvent, ProcessExitedEvent, StopOutcome, StopSignal};
+
+/// 等待进程退出时的轮询间隔
+const EXIT_POLL_INTERVAL_MS: u64 = 50;
+
+/// 进程终止结果
+pub(crate) struct Termination {
+    pub outcome: StopOutcome,
+    pub status: Option<ExitStatus>,
+    pub elapsed_ms: u64,
+}
+
+/// 强制结束整个进程树并等待子进程退出
+fn force_kill(child: &mut ProcessHandle, tree: &ProcessTree) -> Option<ExitStatus> {
+    tree.kill();
+    let _ = child.kill();
+    child.wait()
+}
+
+/// 检查进程树是否已全部退出，返回子进程的退出状态
+///
+/// 子进程已退出但其子孙进程仍在运行时视为仍在运行
+fn check_exited(child: &mut ProcessHandle, tree: &ProcessTree) -> Result<ExitPoll, ()> {
+    match child.try_wait() {
+        Ok(ExitPoll::Exited(status)) if !tree.is_alive() => Ok(ExitPoll::Exited(status)),
+        Ok(_) => Ok(ExitPoll::Running),
+        Err(_) => Err(()),
+    }
+}
+
+/// 停止进程树：发送停止信号，等待至多 `timeout`，超时后强制结束
+pub(crate) fn terminate_child(
+    child: &mut ProcessHandle,
+    tree: &ProcessTree,
+    signal: StopSignal,
+    timeout: Duration,
+) -> Termination {
+    let start = Instant::now();
+    let elapsed_ms = |start: Instant| start.elapsed().as_millis() as u64;
+
+    if let Ok(ExitPoll::Exited(status)) = child.try_wait() {
+        // 清理子进程退出后遗留的子孙进程
+        tree.kill();
+        return Termination {
+            outcome: StopOutcome::AlreadyExited,
+            status,
+            elapsed_ms: 0,
+        };
+    }
+
+    if let Err(e) = tree.signal(child, signal) {
+        eprintln!(
+            "Failed to send {} to process {}: {}",
+            signal.as_str(),
+            child.id(),
+            e
+        );
+        let status = force_kill(child, tree);
+        return Termination {
+            outcome: StopOutcome::ForceKilled,
+            status,
+            elapsed_ms: elapsed_ms(start),
+        };
+    }
+
+    let deadline = start + timeout;
+    loop {
+        match check_exited(child, tree) {
+            Ok(ExitPoll::Exited(status)) => {
+                return Termination {
+                    outcome: StopOutcome::Signaled,
+                    status,
+                    elapsed_ms: elapsed_ms(start),
+                };
+            }
+            Ok(ExitPoll::Running) if Instant::now() < deadline => {
+                thread::sleep(Duration::from_millis(EXIT_POLL_INTERVAL_MS));
+            }
+            _ => break,
+        }
+    }
+
+    let status = force_kill(child, tree);
+    Termination {
+        outcome: StopOutcome::ForceKilled,
+        status,
+        elapsed_ms: elapsed_ms(start),
+    }
+}
+
+/// 停止仍在运行的指定实例并记录退出信息，返回退出事件（由调用方发布）
+///
+/// 用于健康检查失败、就绪超时等内部流程；`pid` 用于确认仍是同一个运行实例，
+/// 进程已被其他流程回收或替换时返回 None。
+/// 结束前执行停止前的钩子（失败时仍然结束），停止后的钩子由调用方在发布退出事件后执行
+pub(super) fn terminate_instance(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    reason: ExitReason,
+) -> Option<ProcessExitedEvent> {
+    let is_instance = |manager: &ProcessManager| {
+        manager
+            .lock()
+            .is_ok_and(|state| state.processes.get(id).is_some_and(|r| r.child.id() == pid))
+    };
+    if !is_instance(manager) {
+        return None;
+    }
+    run_stop_hooks(app, manager, id, HookStage::PreStop);
+
+    // 执行钩子期间进程可能已自行退出或被其他流程结束
+    let mut running = {
+        let mut state = manager.lock().ok()?;
+        if state.processes.get(id).is_none_or(|r| r.child.id() != pid) {
+            return None;
+        }
+        state.processes.remove(id)?
+    };
+
+    let signal = running.config.stop_signal;
+    let timeout = Duration::from_millis(running.config.stop_timeout_ms);
+    emit_process_event(app, id, ProcessEvent::Stopping { signal });
+    let termination = terminate_child(&mut running.child, &running.tree, signal, timeout);
+
+    let event = ProcessExitedEvent {
+        id: id.to_string(),
+        pid,
+        exit: exit_info(termination.status.as_ref(), reason, running.launched_at),
+    };
+    if let Ok(mut state) = manager.lock() {
+        record_exit(&mut state, id, &event.exit);
+    }
+    Some(event)
+}
+
+/// 同时停止多个进程树
+///
+/// 先向所有进程树发送停止信号，再统一等待，每个进程按各自的超时时间强制结束，
+/// 但不晚于全局截止时间 `deadline`。每个进程退出后调用 `on_exit`，返回各进程的退出信息
+pub(crate) fn terminate_all(
+    processes: Vec<(String, RunningProcess)>,
+    deadline: Instant,
+    mut on_exit: impl FnMut(&ProcessExitedEvent),
+) -> Vec<ProcessExitedEvent> {
+    let start = Instant::now();
+    let mut pending: Vec<(String, RunningProcess, Instant)> = Vec::new();
+    let mut exits: Vec<ProcessExitedEvent> = Vec::new();
+
+    for (id, mut running) in processes {
+        if let Ok(ExitPoll::Exited(status)) = running.child.try_wait() {
+            running.tree.kill();
+            let reason = classify_exit(&running.config, status.as_ref(), running.cgroup.as_ref());
+            let event = ProcessExitedEvent {
+                pid: running.child.id(),
+                id,
+                exit: exit_info(status.as_ref(), reason, running.launched_at),
+            };
+            on_exit(&event);
+            exits.push(event);
+            continue;
+        }
+        let signal = running.config.stop_signal;
+        if let Err(e) = running.tree.signal(&mut running.child, signal) {
+            eprintln!(
+                "Failed to send {} to process {}: {}",
+                signal.as_str(),
+                id,
+                e
+            );
+            let status = force_kill(&mut running.child, &running.tree);
+            println!("Killed process: {}", id);
+            let event = ProcessExitedEvent {
+                pid: running.child.id(),
+                id,
+                exit: exit_info(
+                    status.as_ref(),
+                    ExitReason::AppShutdown,
+                    running.launched_at,
+                ),
+            };
+            on_exit(&event);
+            exits.push(event);
+            continue;
+        }
+        let timeout = start + Duration::from_millis(running.config.stop_timeout_ms);
+        pending.push((id, running, timeout.min(deadline)));
+    }
+
+    while !pending.is_empty() {
+        let now = Instant::now();
+        pending.retain_mut(|(id, running, deadline)| {
+            let status = match check_exited(&mut running.child, &running.tree) {
+                Ok(ExitPoll::Running) if now < *deadline => return true,
+                Ok(ExitPoll::Running) => {
+                    println!("Killed process after timeout: {}", id);
+                    force_kill(&mut running.child, &running.tree)
+                }
+                Ok(ExitPoll::Exited(status)) => {
+                    println!("Stopped process: {}", id);
+                    status
+                }
+                Err(_) => None,
+            };
+            let event = ProcessExitedEvent {
+                id: id.clone(),
+                pid: running.child.id(),
+                exit: exit_info(
+                    status.as_ref(),
+                    ExitReason::AppShutdown,
+                    running.launched_at,
+                ),
+            };
+            on_exit(&event);
+            exits.push(event);
+            false
+        });
+        if !pending.is_empty() {
+            thread::sleep(Duration::from_millis(EXIT_POLL_INTERVAL_MS));
+        }
+    }
+
+    exits
+}
@@ -0,0 +1,378 @@
+//! 资源阈值
+//!
+//! 每次采样后检查进程配置的阈值规则：常驻内存或 CPU 占用持续超过阈值达到设定时长后触发一次，
+//! 回落到阈值以下后重新计时。触发时记录到数据库（按时间归入本次运行的运行记录）和运行中
+//! 实例的进程信息，并发送 `threshold-exceeded` 事件，再按规则的操作重启或停止进程（退出原因
+//! 为 threshold-exceeded）。依赖资源占用采样，仅支持 Linux
+
+use std::collections::HashMap;
+use std::thread;
+use tauri::{AppHandle, Manager};
+
+use super::events::emit_process_event;
+use super::exit::publish_exits;
+use super::hooks::{run_stop_hooks, HookStage};
+use super::state::ProcessManager;
+use super::supervisor::restart_instance;
+use super::termination::terminate_instance;
+use super::types::{
+    ExitReason, ProcessEvent, ProcessMetrics, ProcessMetricsEvent, ThresholdAction,
+    ThresholdMetric, ThresholdRule, ThresholdTrigger,
+};
+use crate::storage::{save_threshold_trigger, DbState};
+
+/// 单条规则的计时状态
+#[derive(Clone, Copy, Default)]
+struct RuleState {
+    /// 本次连续超过阈值的第一个采样时间（毫秒）
+    above_since: Option<i64>,
+    /// 本次连续超过阈值期间是否已触发
+    fired: bool,
+}
+
+/// 某个运行实例的规则计时状态，规则被修改或实例被替换后重新计时
+struct InstanceState {
+    pid: u32,
+    rules: Vec<ThresholdRule>,
+    states: Vec<RuleState>,
+}
+
+/// 资源阈值检查状态（由采样线程持有）
+#[derive(Default)]
+pub(super) struct ThresholdMonitor {
+    instances: HashMap<String, InstanceState>,
+}
+
+impl ThresholdMonitor {
+    /// 根据本次采样结果检查阈值规则并执行触发的操作
+    pub fn check(
+        &mut self,
+        app: &AppHandle,
+        manager: &ProcessManager,
+        samples: &[ProcessMetricsEvent],
+    ) {
+        // 使用最新配置，只检查仍在运行的同一实例
+        let rules: HashMap<&str, Vec<ThresholdRule>> = match manager.lock() {
+            Ok(state) => samples
+                .iter()
+                .filter(|sample| {
+                    state
+                        .processes
+                        .get(&sample.id)
+                        .is_some_and(|running| running.child.id() == sample.pid)
+                })
+                .filter_map(|sample| {
+                    let config = state.configs.get(&sample.id)?;
+                    (!config.threshold_rules.is_empty())
+                        .then(|| (sample.id.as_str(), config.threshold_rules.clone()))
+                })
+                .collect(),
+            Err(_) => return,
+        };
+        for (id, pid, trigger) in self.evaluate(&rules, samples) {
+            trigger_action(app, manager, &id, pid, trigger);
+        }
+    }
+
+    /// 按各进程的规则更新计时状态，返回本次触发的规则（进程 ID、PID 和触发记录）
+    fn evaluate(
+        &mut self,
+        rules: &HashMap<&str, Vec<ThresholdRule>>,
+        samples: &[ProcessMetricsEvent],
+    ) -> Vec<(String, u32, ThresholdTrigger)> {
+        self.instances
+            .retain(|id, _| rules.contains_key(id.as_str()));
+
+        let mut fired = Vec::new();
+        for sample in samples {
+            let Some(rules) = rules.get(sample.id.as_str()) else {
+                continue;
+            };
+            let instance =
+                self.instances
+                    .entry(sample.id.clone())
+                    .or_insert_with(|| InstanceState {
+                        pid: sample.pid,
+                        rules: Vec::new(),
+                        states: Vec::new(),
+                    });
+            if instance.pid != sample.pid || instance.rules != *rules {
+                instance.pid = sample.pid;
+                instance.rules = rules.clone();
+                instance.states = vec![RuleState::default(); rules.len()];
+            }
+
+            for (rule, state) in instance.rules.iter().zip(instance.states.iter_mut()) {
+                let value = metric_value(&sample.metrics, rule.metric);
+                if value <= rule.above {
+                    *state = RuleState::default();
+                    continue;
+                }
+                let since = *state.above_since.get_or_insert(sample.metrics.sampled_at);
+                if state.fired
+                    || sample.metrics.sampled_at - since < rule.duration_secs as i64 * 1000
+                {
+                    continue;
+                }
+                state.fired = true;
+
+                let trigger = ThresholdTrigger {
+                    triggered_at: sample.metrics.sampled_at / 1000,
+                    rule: rule.clone(),
+                    value,
+                };
+                fired.push((sample.id.clone(), sample.pid, trigger));
+                // 重启或停止后不再检查该实例的其余规则
+                if rule.action != ThresholdAction::Warn {
+                    break;
+                }
+            }
+        }
+        fired
+    }
+}
+
+/// 规则所检查的指标的采样值
+fn metric_value(metrics: &ProcessMetrics, metric: ThresholdMetric) -> f64 {
+    match metric {
+        ThresholdMetric::Rss => metrics.rss_bytes as f64,
+        ThresholdMetric::Cpu => metrics.cpu_percent,
+    }
+}
+
+/// 记录规则的触发、发送事件并执行操作（重启和停止在独立线程中执行，不阻塞采样）
+fn trigger_action(
+    app: &AppHandle,
+    manager: &ProcessManager,
+    id: &str,
+    pid: u32,
+    trigger: ThresholdTrigger,
+) {
+    eprintln!(
+        "Process {} exceeded threshold ({:?} above {} for {} s, value {}), action: {:?}",
+        id,
+        trigger.rule.metric,
+        trigger.rule.above,
+        trigger.rule.duration_secs,
+        trigger.value,
+        trigger.rule.action
+    );
+    if let Some(db) = app.try_state::<DbState>() {
+        if let Ok(conn) = db.0.lock() {
+            if let Err(e) = save_threshold_trigger(&conn, id, &trigger) {
+                eprintln!("Failed to save threshold trigger for process {}: {}", id, e);
+            }
+        }
+    }
+    if let Ok(mut state) = manager.lock() {
+        // 只归入触发规则的运行实例
+        if state
+            .processes
+            .get(id)
+            .is_some_and(|running| running.child.id() == pid)
+        {
+            state
+                .runtime
+                .entry(id.to_string())
+                .or_default()
+                .threshold_triggers
+                .push(trigger.clone());
+        }
+    }
+    let action = trigger.rule.action;
+    emit_process_event(
+        app,
+        id,
+        ProcessEvent::ThresholdExceeded {
+            rule: trigger.rule,
+            value: trigger.value,
+        },
+    );
+
+    if action == ThresholdAction::Warn {
+        return;
+    }
+    let app = app.clone();
+    let manager = manager.clone();
+    let id = id.to_string();
+    thread::spawn(move || match action {
+        ThresholdAction::Restart => {
+            restart_instance(&app, &manager, &id, pid, ExitReason::ThresholdExceeded);
+        }
+        ThresholdAction::Stop => {
+            if let Some(event) =
+                terminate_instance(&app, &manager, &id, pid, ExitReason::ThresholdExceeded)
+            {
+                publish_exits(&app, std::slice::from_ref(&event));
+                run_stop_hooks(&app, &manager, &id, HookStage::PostStop);
+            }
+        }
+        ThresholdAction::Warn => {}
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        metric: ThresholdMetric,
+        above: f64,
+        duration_secs: u64,
+        action: ThresholdAction,
+    ) -> ThresholdRule {
+        ThresholdRule {
+            metric,
+            above,
+            duration_secs,
+            action,
+        }
+    }
+
+    fn sample(
+        id: &str,
+        pid: u32,
+        sampled_at: i64,
+        cpu_percent: f64,
+        rss_bytes: u64,
+    ) -> ProcessMetricsEvent {
+        ProcessMetricsEvent {
+            id: id.to_string(),
+            pid,
+            metrics: ProcessMetrics {
+                sampled_at,
+                cpu_percent,
+                rss_bytes,
+                threads: 1,
+                processes: 1,
+                read_bytes_per_sec: 0.0,
+                write_bytes_per_sec: 0.0,
+            },
+        }
+    }
+
+    /// 依次输入各时刻（秒）的 CPU 采样，返回触发时刻
+    fn fire_times(
+        monitor: &mut ThresholdMonitor,
+        rules: &[ThresholdRule],
+        cpu: &[(i64, f64)],
+    ) -> Vec<i64> {
+        let rules = HashMap::from([("p", rules.to_vec())]);
+        cpu.iter()
+            .flat_map(|&(secs, value)| {
+                monitor.evaluate(&rules, &[sample("p", 1, secs * 1000, value, 0)])
+            })
+            .map(|(_, _, trigger)| trigger.triggered_at)
+            .collect()
+    }
+
+    #[test]
+    fn fires_once_per_continuous_breach() {
+        let mut monitor = ThresholdMonitor::default();
+        let rules = [rule(ThresholdMetric::Cpu, 50.0, 3, ThresholdAction::Warn)];
+        let samples = [
+            (0, 60.0),
+            (1, 70.0),
+            (2, 80.0),
+            (3, 90.0),
+            (4, 90.0),
+            (5, 90.0),
+        ];
+        assert_eq!(fire_times(&mut monitor, &rules, &samples), [3]);
+    }
+
+    #[test]
+    fn resets_when_value_drops_back() {
+        let mut monitor = ThresholdMonitor::default();
+        let rules = [rule(ThresholdMetric::Cpu, 50.0, 2, ThresholdAction::Warn)];
+        let samples = [
+            (0, 60.0),
+            (1, 60.0),
+            // 等于阈值视为未超过，重新计时
+            (2, 50.0),
+            (3, 60.0),
+            (4, 60.0),
+            (5, 60.0),
+            (6, 10.0),
+            (7, 60.0),
+            (8, 60.0),
+            (9, 60.0),
+        ];
+        assert_eq!(fire_times(&mut monitor, &rules, &samples), [5, 9]);
+    }
+
+    #[test]
+    fn zero_duration_fires_on_first_sample() {
+        let mut monitor = ThresholdMonitor::default();
+        let rules = HashMap::from([(
+            "p",
+            vec![rule(ThresholdMetric::Rss, 1000.0, 0, ThresholdAction::Stop)],
+        )]);
+        assert!(monitor
+            .evaluate(&rules, &[sample("p", 1, 0, 0.0, 1000)])
+            .is_empty());
+        let fired = monitor.evaluate(&rules, &[sample("p", 1, 1000, 0.0, 2000)]);
+        assert_eq!(fired.len(), 1);
+        let (id, pid, trigger) = &fired[0];
+        assert_eq!((id.as_str(), *pid), ("p", 1));
+        assert_eq!(trigger.value, 2000.0);
+        assert_eq!(trigger.triggered_at, 1);
+    }
+
+    #[test]
+    fn stops_checking_rules_after_restart_or_stop() {
+        let mut monitor = ThresholdMonitor::default();
+        let rules = HashMap::from([(
+            "p",
+            vec![
+                rule(ThresholdMetric::Cpu, 50.0, 0, ThresholdAction::Warn),
+                rule(ThresholdMetric::Cpu, 50.0, 0, ThresholdAction::Restart),
+                rule(ThresholdMetric::Cpu, 50.0, 0, ThresholdAction::Stop),
+            ],
+        )]);
+        let fired = monitor.evaluate(&rules, &[sample("p", 1, 0, 90.0, 0)]);
+        let actions: Vec<ThresholdAction> = fired.iter().map(|(_, _, t)| t.rule.action).collect();
+        assert_eq!(actions, [ThresholdAction::Warn, ThresholdAction::Restart]);
+    }
+
+    #[test]
+    fn new_instance_or_changed_rules_restart_timing() {
+        let mut monitor = ThresholdMonitor::default();
+        let warn = rule(ThresholdMetric::Cpu, 50.0, 2, ThresholdAction::Warn);
+        let rules = HashMap::from([("p", vec![warn.clone()])]);
+        assert!(monitor
+            .evaluate(&rules, &[sample("p", 1, 0, 90.0, 0)])
+            .is_empty());
+        // 进程被替换：从新实例的第一个采样开始计时
+        assert!(monitor
+            .evaluate(&rules, &[sample("p", 2, 2000, 90.0, 0)])
+            .is_empty());
+        assert_eq!(
+            monitor
+                .evaluate(&rules, &[sample("p", 2, 4000, 90.0, 0)])
+                .len(),
+            1
+        );
+
+        // 规则被修改：重新计时，可以再次触发
+        let changed = HashMap::from([(
+            "p",
+            vec![rule(ThresholdMetric::Cpu, 60.0, 2, ThresholdAction::Warn)],
+        )]);
+        assert!(monitor
+            .evaluate(&changed, &[sample("p", 2, 5000, 90.0, 0)])
+            .is_empty());
+        assert_eq!(
+            monitor
+                .evaluate(&changed, &[sample("p", 2, 7000, 90.0, 0)])
+                .len(),
+            1
+        );
+
+        // 不再有规则的进程被移除
+        assert!(monitor
+            .evaluate(&HashMap::new(), &[sample("p", 2, 8000, 90.0, 0)])
+            .is_empty());
+        assert!(monitor.instances.is_empty());
+    }
+}
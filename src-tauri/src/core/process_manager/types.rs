@@ -22,15 +22,279 @@ pub enum ProcessMode {
     Import,
 }
 
+/// 进程类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcessKind {
+    /// 长期运行的服务，退出即视为停止
+    #[default]
+    Service,
+    /// 运行至结束的一次性任务（如迁移、备份脚本），正常退出视为成功完成
+    Task,
+}
+
+impl ProcessKind {
+    /// 数据库中存储的类型名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProcessKind::Service => "service",
+            ProcessKind::Task => "task",
+        }
+    }
+
+    /// 从类型名称解析（未知名称视为服务）
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "task" => ProcessKind::Task,
+            _ => ProcessKind::Service,
+        }
+    }
+}
+
 /// 进程状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ProcessStatus {
     Running,
     Stopped,
+    /// 进程异常退出，等待自动重启
+    Restarting,
+    /// 任务最近一次运行成功完成
+    Completed,
     Error,
 }
 
+/// 重启策略（语义参考 Docker 的 restart policy）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "policy", rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// 从不自动重启
+    #[default]
+    No,
+    /// 仅在异常退出时重启，可限制连续重试次数
+    OnFailure {
+        #[serde(default)]
+        max_retries: Option<u32>,
+    },
+    /// 退出后总是重启，应用启动时也会启动该进程
+    Always,
+    /// 退出后总是重启，但被用户手动停止后，应用启动时不再启动该进程
+    UnlessStopped,
+}
+
+impl RestartPolicy {
+    /// 数据库中存储的策略名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RestartPolicy::No => "no",
+            RestartPolicy::OnFailure { .. } => "on-failure",
+            RestartPolicy::Always => "always",
+            RestartPolicy::UnlessStopped => "unless-stopped",
+        }
+    }
+
+    /// 从策略名称和最大重试次数构造（未知名称视为 `no`）
+    pub fn from_parts(name: &str, max_retries: Option<u32>) -> Self {
+        match name {
+            "on-failure" => RestartPolicy::OnFailure { max_retries },
+            "always" => RestartPolicy::Always,
+            "unless-stopped" => RestartPolicy::UnlessStopped,
+            _ => RestartPolicy::No,
+        }
+    }
+
+    /// 是否启用了自动重启
+    pub fn is_enabled(&self) -> bool {
+        *self != RestartPolicy::No
+    }
+}
+
+/// 停止进程时首先发送的信号
+///
+/// Windows 上 SIGKILL 以外的信号均以 Ctrl-Break 事件发送
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StopSignal {
+    #[default]
+    SigTerm,
+    SigInt,
+    SigHup,
+    SigQuit,
+    SigKill,
+}
+
+impl StopSignal {
+    /// 数据库中存储的信号名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopSignal::SigTerm => "sigterm",
+            StopSignal::SigInt => "sigint",
+            StopSignal::SigHup => "sighup",
+            StopSignal::SigQuit => "sigquit",
+            StopSignal::SigKill => "sigkill",
+        }
+    }
+
+    /// 从信号名称解析（未知名称视为 SIGTERM）
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "sigint" => StopSignal::SigInt,
+            "sighup" => StopSignal::SigHup,
+            "sigquit" => StopSignal::SigQuit,
+            "sigkill" => StopSignal::SigKill,
+            _ => StopSignal::SigTerm,
+        }
+    }
+}
+
+/// 启动前清理残留实例的方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum StaleCleanup {
+    /// 不清理
+    #[default]
+    None,
+    /// 结束可执行文件路径与本进程完全相同的进程（仅 Linux 和 Windows）
+    ExePath,
+    /// 只结束上次启动时记录的 PID，且启动时间需与记录一致
+    PidFile,
+}
+
+impl StaleCleanup {
+    /// 数据库中存储的方式名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StaleCleanup::None => "none",
+            StaleCleanup::ExePath => "exe-path",
+            StaleCleanup::PidFile => "pid-file",
+        }
+    }
+
+    /// 从方式名称解析（未知名称视为不清理）
+    pub fn parse(name: &str) -> Self {
+        match name {
+            "exe-path" => StaleCleanup::ExePath,
+            "pid-file" => StaleCleanup::PidFile,
+            _ => StaleCleanup::None,
+        }
+    }
+}
+
+/// 钩子命令执行失败（非零退出码或超时）时的处理方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookFailure {
+    /// 中止后续钩子，启动前 / 启动后 / 停止前的钩子失败时启动或停止失败
+    #[default]
+    Fail,
+    /// 忽略失败，继续执行
+    Continue,
+}
+
+/// 生命周期钩子命令
+///
+/// 与进程使用相同的工作目录和环境变量执行，输出以 `hook` 类型记录到进程输出中
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HookCommand {
+    #[serde(default)]
+    pub command_type: CommandType,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 超时时间（毫秒），超时后结束钩子命令并视为失败
+    #[serde(default = "default_hook_timeout_ms")]
+    pub timeout_ms: u64,
+    #[serde(default)]
+    pub on_failure: HookFailure,
+}
+
+fn default_hook_timeout_ms() -> u64 {
+    30_000
+}
+
+/// 资源限制（仅 Linux），未设置的项不限制
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ResourceLimits {
+    /// 最大内存（字节）：cgroup 的 memory.max，没有 cgroup 时为虚拟内存上限
+    pub max_memory_bytes: Option<u64>,
+    /// CPU 配额（百分比，100 表示一个核心），需要 cgroup
+    pub cpu_quota_percent: Option<u32>,
+    /// 最多打开的文件数
+    pub max_open_files: Option<u64>,
+    /// 最大进程数：cgroup 的 pids.max，没有 cgroup 时为所属用户的进程数上限
+    pub max_processes: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// 是否没有设置任何限制
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 是否设置了需要 cgroup 实现的限制
+    pub fn needs_cgroup(&self) -> bool {
+        self.max_memory_bytes.is_some()
+            || self.cpu_quota_percent.is_some()
+            || self.max_processes.is_some()
+    }
+}
+
+/// 资源阈值规则的指标
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThresholdMetric {
+    /// 常驻内存（字节）
+    Rss,
+    /// CPU 占用（百分比，100 表示占满一个核心）
+    Cpu,
+}
+
+/// 资源阈值规则触发后的操作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThresholdAction {
+    /// 发送警告事件
+    Warn,
+    /// 按停止配置停止后通过退避重启流程重启（不受重启策略限制，但计入崩溃循环隔离）
+    Restart,
+    /// 按停止配置停止
+    Stop,
+}
+
+/// 资源阈值规则：进程树的资源占用持续超过阈值一段时间后执行操作（仅 Linux）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThresholdRule {
+    pub metric: ThresholdMetric,
+    /// 阈值，单位与指标相同
+    pub above: f64,
+    /// 持续超过阈值的时长（秒）
+    pub duration_secs: u64,
+    pub action: ThresholdAction,
+}
+
+/// 资源阈值规则的一次触发
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThresholdTrigger {
+    /// 触发时间（秒）
+    pub triggered_at: i64,
+    pub rule: ThresholdRule,
+    /// 触发时的采样值
+    pub value: f64,
+}
+
+/// 伪终端窗口大小
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct PtySize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for PtySize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
 /// 进程配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessConfig {
@@ -45,9 +309,504 @@ pub struct ProcessConfig {
     pub working_dir: String,
     pub source_path: Option<String>,
     pub env: HashMap<String, String>,
-    pub auto_restart: bool,
+    /// 进程类型：服务或一次性任务
+    #[serde(default)]
+    pub kind: ProcessKind,
+    /// 重启策略
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// 除 0 之外视为正常退出的退出码，不会触发 on-failure 重启
+    #[serde(default)]
+    pub success_exit_codes: Vec<i32>,
     pub auto_start: bool, // 跟随应用启动
     pub created_at: i64,
+    /// 自动重启的初始退避延迟（毫秒），每次连续重启翻倍
+    #[serde(default = "default_restart_delay_ms")]
+    pub restart_delay_ms: u64,
+    /// 自动重启的最大退避延迟（毫秒）
+    #[serde(default = "default_restart_max_delay_ms")]
+    pub restart_max_delay_ms: u64,
+    /// 崩溃循环检测：时间窗口内退出次数超过该值即隔离进程（0 表示不检测）
+    #[serde(default = "default_crash_loop_max_exits")]
+    pub crash_loop_max_exits: u32,
+    /// 崩溃循环检测的时间窗口（秒）
+    #[serde(default = "default_crash_loop_window_secs")]
+    pub crash_loop_window_secs: u64,
+    /// 停止进程时发送的信号
+    #[serde(default)]
+    pub stop_signal: StopSignal,
+    /// 发送停止信号后等待进程退出的时间（毫秒），超时后强制结束
+    #[serde(default = "default_stop_timeout_ms")]
+    pub stop_timeout_ms: u64,
+    /// 健康检查（可选）
+    #[serde(default)]
+    pub health_check: Option<HealthCheckConfig>,
+    /// 就绪条件（可选），未设置时进程启动后即视为就绪
+    #[serde(default)]
+    pub readiness: Option<ReadinessConfig>,
+    /// 依赖的进程 ID，启动前先启动依赖，停止依赖前先停止本进程
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// 启动前是否等待依赖就绪
+    #[serde(default)]
+    pub wait_for_dependencies: bool,
+    /// 自动启动的优先级，数值小的先启动
+    #[serde(default)]
+    pub start_order: i32,
+    /// 自动启动时，启动本进程前等待的时间（毫秒）
+    #[serde(default)]
+    pub start_delay_ms: u64,
+    /// 启动前清理残留实例的方式
+    #[serde(default)]
+    pub stale_cleanup: StaleCleanup,
+    /// 退出应用时保持运行（在独立会话中运行，输出写入日志文件）
+    #[serde(default)]
+    pub survive_app_exit: bool,
+    /// 启动前依次执行的钩子命令
+    #[serde(default)]
+    pub pre_start: Vec<HookCommand>,
+    /// 启动后（设置了就绪条件时为就绪后）依次执行的钩子命令
+    #[serde(default)]
+    pub post_start: Vec<HookCommand>,
+    /// 停止前依次执行的钩子命令
+    #[serde(default)]
+    pub pre_stop: Vec<HookCommand>,
+    /// 停止后依次执行的钩子命令
+    #[serde(default)]
+    pub post_stop: Vec<HookCommand>,
+    /// 在伪终端中运行（交互式程序、根据终端决定是否输出颜色的程序），仅支持 Unix
+    #[serde(default)]
+    pub use_pty: bool,
+    /// 伪终端的初始窗口大小
+    #[serde(default)]
+    pub pty_size: PtySize,
+    /// 资源限制
+    #[serde(default)]
+    pub resource_limits: ResourceLimits,
+    /// 资源阈值规则
+    #[serde(default)]
+    pub threshold_rules: Vec<ThresholdRule>,
+    /// 任务类型的进程：依赖它的进程等待其运行结束（包括重试）的最长时间（毫秒）
+    #[serde(default = "default_task_timeout_ms")]
+    pub task_timeout_ms: u64,
+}
+
+fn default_restart_delay_ms() -> u64 {
+    1000
+}
+
+fn default_task_timeout_ms() -> u64 {
+    10 * 60 * 1000
+}
+
+fn default_restart_max_delay_ms() -> u64 {
+    60_000
+}
+
+fn default_crash_loop_max_exits() -> u32 {
+    5
+}
+
+fn default_crash_loop_window_secs() -> u64 {
+    60
+}
+
+fn default_stop_timeout_ms() -> u64 {
+    10_000
+}
+
+impl Default for ProcessConfig {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            mode: ProcessMode::Fork,
+            command_type: CommandType::default(),
+            command: String::new(),
+            args: Vec::new(),
+            working_dir: String::new(),
+            source_path: None,
+            env: HashMap::new(),
+            kind: ProcessKind::default(),
+            restart_policy: RestartPolicy::default(),
+            success_exit_codes: Vec::new(),
+            auto_start: false,
+            created_at: 0,
+            restart_delay_ms: default_restart_delay_ms(),
+            restart_max_delay_ms: default_restart_max_delay_ms(),
+            crash_loop_max_exits: default_crash_loop_max_exits(),
+            crash_loop_window_secs: default_crash_loop_window_secs(),
+            stop_signal: StopSignal::default(),
+            stop_timeout_ms: default_stop_timeout_ms(),
+            health_check: None,
+            readiness: None,
+            depends_on: Vec::new(),
+            wait_for_dependencies: false,
+            start_order: 0,
+            start_delay_ms: 0,
+            stale_cleanup: StaleCleanup::default(),
+            survive_app_exit: false,
+            pre_start: Vec::new(),
+            post_start: Vec::new(),
+            pre_stop: Vec::new(),
+            post_stop: Vec::new(),
+            use_pty: false,
+            pty_size: PtySize::default(),
+            resource_limits: ResourceLimits::default(),
+            threshold_rules: Vec::new(),
+            task_timeout_ms: default_task_timeout_ms(),
+        }
+    }
+}
+
+/// 健康检查探测方式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum HealthProbe {
+    /// 连接 TCP 端口
+    Tcp {
+        #[serde(default = "default_probe_host")]
+        host: String,
+        port: u16,
+    },
+    /// 发送 HTTP GET 请求并检查状态码
+    Http {
+        url: String,
+        #[serde(default = "default_expected_status")]
+        expected_status: u16,
+    },
+    /// 执行探测命令并检查退出码
+    Command {
+        command: String,
+        #[serde(default)]
+        expected_exit_code: i32,
+    },
+}
+
+fn default_probe_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_expected_status() -> u16 {
+    200
+}
+
+/// 健康检查配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthCheckConfig {
+    #[serde(flatten)]
+    pub probe: HealthProbe,
+    /// 检查间隔（毫秒）
+    #[serde(default = "default_health_interval_ms")]
+    pub interval_ms: u64,
+    /// 单次检查超时（毫秒）
+    #[serde(default = "default_health_timeout_ms")]
+    pub timeout_ms: u64,
+    /// 连续失败多少次后视为不健康
+    #[serde(default = "default_health_failure_threshold")]
+    pub failure_threshold: u32,
+    /// 不健康时是否重启进程
+    #[serde(default)]
+    pub restart_on_failure: bool,
+}
+
+fn default_health_interval_ms() -> u64 {
+    10_000
+}
+
+fn default_health_timeout_ms() -> u64 {
+    3_000
+}
+
+fn default_health_failure_threshold() -> u32 {
+    3
+}
+
+/// 就绪条件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ReadinessProbe {
+    /// stdout / stderr 中出现匹配正则表达式的行
+    OutputMatch { pattern: String },
+    /// TCP 端口接受连接
+    Tcp {
+        #[serde(default = "default_probe_host")]
+        host: String,
+        port: u16,
+    },
+    /// 健康检查（使用 `health_check` 配置的探测方式）通过
+    HealthCheck,
+}
+
+/// 就绪检测配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReadinessConfig {
+    #[serde(flatten)]
+    pub probe: ReadinessProbe,
+    /// 启动超时（毫秒），超时未就绪视为启动失败
+    #[serde(default = "default_startup_timeout_ms")]
+    pub startup_timeout_ms: u64,
+    /// 启动超时后是否结束进程
+    #[serde(default)]
+    pub kill_on_timeout: bool,
+}
+
+fn default_startup_timeout_ms() -> u64 {
+    30_000
+}
+
+/// 健康状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    Healthy,
+    Unhealthy,
+    /// 未配置健康检查、进程未运行或尚未完成检查
+    #[default]
+    Unknown,
+}
+
+/// 进程高级选项
+///
+/// 用于新增/编辑进程命令，未提供的字段保持原值（新增时使用默认值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessOptions {
+    pub kind: Option<ProcessKind>,
+    pub success_exit_codes: Option<Vec<i32>>,
+    pub restart_delay_ms: Option<u64>,
+    pub restart_max_delay_ms: Option<u64>,
+    pub crash_loop_max_exits: Option<u32>,
+    pub crash_loop_window_secs: Option<u64>,
+    pub stop_signal: Option<StopSignal>,
+    pub stop_timeout_ms: Option<u64>,
+    /// 健康检查：未提供时保持原值，为 null 时移除
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub health_check: Option<Option<HealthCheckConfig>>,
+    /// 就绪条件：未提供时保持原值，为 null 时移除
+    #[serde(default, deserialize_with = "deserialize_some")]
+    pub readiness: Option<Option<ReadinessConfig>>,
+    pub depends_on: Option<Vec<String>>,
+    pub wait_for_dependencies: Option<bool>,
+    pub start_order: Option<i32>,
+    pub start_delay_ms: Option<u64>,
+    pub stale_cleanup: Option<StaleCleanup>,
+    pub survive_app_exit: Option<bool>,
+    pub pre_start: Option<Vec<HookCommand>>,
+    pub post_start: Option<Vec<HookCommand>>,
+    pub pre_stop: Option<Vec<HookCommand>>,
+    pub post_stop: Option<Vec<HookCommand>>,
+    pub use_pty: Option<bool>,
+    pub pty_size: Option<PtySize>,
+    pub resource_limits: Option<ResourceLimits>,
+    pub threshold_rules: Option<Vec<ThresholdRule>>,
+    pub task_timeout_ms: Option<u64>,
+}
+
+/// 区分字段缺失（None）和显式的 null（Some(None)）
+fn deserialize_some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl ProcessOptions {
+    /// 将选项应用到进程配置
+    pub fn apply_to(self, config: &mut ProcessConfig) {
+        if let Some(kind) = self.kind {
+            config.kind = kind;
+        }
+        if let Some(codes) = self.success_exit_codes {
+            config.success_exit_codes = codes;
+        }
+        if let Some(delay) = self.restart_delay_ms {
+            config.restart_delay_ms = delay;
+        }
+        if let Some(max_delay) = self.restart_max_delay_ms {
+            config.restart_max_delay_ms = max_delay;
+        }
+        if let Some(max_exits) = self.crash_loop_max_exits {
+            config.crash_loop_max_exits = max_exits;
+        }
+        if let Some(window) = self.crash_loop_window_secs {
+            config.crash_loop_window_secs = window;
+        }
+        if let Some(signal) = self.stop_signal {
+            config.stop_signal = signal;
+        }
+        if let Some(timeout) = self.stop_timeout_ms {
+            config.stop_timeout_ms = timeout;
+        }
+        if let Some(health_check) = self.health_check {
+            config.health_check = health_check;
+        }
+        if let Some(readiness) = self.readiness {
+            config.readiness = readiness;
+        }
+        if let Some(depends_on) = self.depends_on {
+            config.depends_on = depends_on;
+        }
+        if let Some(wait) = self.wait_for_dependencies {
+            config.wait_for_dependencies = wait;
+        }
+        if let Some(order) = self.start_order {
+            config.start_order = order;
+        }
+        if let Some(delay) = self.start_delay_ms {
+            config.start_delay_ms = delay;
+        }
+        if let Some(cleanup) = self.stale_cleanup {
+            config.stale_cleanup = cleanup;
+        }
+        if let Some(survive) = self.survive_app_exit {
+            config.survive_app_exit = survive;
+        }
+        if let Some(hooks) = self.pre_start {
+            config.pre_start = hooks;
+        }
+        if let Some(hooks) = self.post_start {
+            config.post_start = hooks;
+        }
+        if let Some(hooks) = self.pre_stop {
+            config.pre_stop = hooks;
+        }
+        if let Some(hooks) = self.post_stop {
+            config.post_stop = hooks;
+        }
+        if let Some(use_pty) = self.use_pty {
+            config.use_pty = use_pty;
+        }
+        if let Some(size) = self.pty_size {
+            config.pty_size = size;
+        }
+        if let Some(limits) = self.resource_limits {
+            config.resource_limits = limits;
+        }
+        if let Some(rules) = self.threshold_rules {
+            config.threshold_rules = rules;
+        }
+        if let Some(timeout) = self.task_timeout_ms {
+            config.task_timeout_ms = timeout;
+        }
+    }
+}
+
+/// 崩溃循环隔离信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineInfo {
+    /// 隔离原因
+    pub reason: String,
+    /// 隔离时间（秒）
+    pub since: i64,
+}
+
+/// 进程退出原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExitReason {
+    /// 进程自行正常退出
+    Exited,
+    /// 进程异常退出（非成功退出码或被信号终止）
+    Crashed,
+    /// 用户手动停止
+    UserStopped,
+    /// 停止超时后被强制结束
+    KilledByTimeout,
+    /// 健康检查连续失败后被重启
+    HealthCheckFailed,
+    /// 启动超时未就绪被结束
+    ReadinessTimeout,
+    /// 应用退出时停止
+    AppShutdown,
+    /// 启动后的钩子命令失败被结束
+    HookFailed,
+    /// 超出内存限制被 OOM 结束（由进程所在的 cgroup 记录）
+    OomKilled,
+    /// 资源占用超过阈值规则被停止或重启
+    ThresholdExceeded,
+}
+
+impl ExitReason {
+    /// 数据库中存储的原因名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::Exited => "exited",
+            ExitReason::Crashed => "crashed",
+            ExitReason::UserStopped => "user-stopped",
+            ExitReason::KilledByTimeout => "killed-by-timeout",
+            ExitReason::HealthCheckFailed => "health-check-failed",
+            ExitReason::ReadinessTimeout => "readiness-timeout",
+            ExitReason::AppShutdown => "app-shutdown",
+            ExitReason::HookFailed => "hook-failed",
+            ExitReason::OomKilled => "oom-killed",
+            ExitReason::ThresholdExceeded => "threshold-exceeded",
+        }
+    }
+
+    /// 从原因名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "exited" => Some(ExitReason::Exited),
+            "crashed" => Some(ExitReason::Crashed),
+            "user-stopped" => Some(ExitReason::UserStopped),
+            "killed-by-timeout" => Some(ExitReason::KilledByTimeout),
+            "health-check-failed" => Some(ExitReason::HealthCheckFailed),
+            "readiness-timeout" => Some(ExitReason::ReadinessTimeout),
+            "app-shutdown" => Some(ExitReason::AppShutdown),
+            "hook-failed" => Some(ExitReason::HookFailed),
+            "oom-killed" => Some(ExitReason::OomKilled),
+            "threshold-exceeded" => Some(ExitReason::ThresholdExceeded),
+            _ => None,
+        }
+    }
+}
+
+/// 进程最近一次退出的信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitInfo {
+    /// 退出码，被信号终止时为 None
+    pub code: Option<i32>,
+    /// 终止进程的信号（仅 Unix）
+    pub signal: Option<i32>,
+    /// 退出时间（秒）
+    pub exited_at: i64,
+    pub reason: ExitReason,
+    /// 本次运行的时长（毫秒）
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+}
+
+/// 进程的一次运行记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessRun {
+    pub id: i64,
+    pub process_id: String,
+    /// 启动时间（秒）
+    pub started_at: i64,
+    /// 退出时间（秒）
+    pub exited_at: i64,
+    /// 运行时长（毫秒）
+    pub duration_ms: u64,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    /// 退出原因，正常退出（exited）即运行成功
+    pub reason: ExitReason,
+    /// 本次运行中触发的资源阈值规则
+    pub threshold_triggers: Vec<ThresholdTrigger>,
+}
+
+/// 运行中进程的实例记录，用于应用重启后重新接管仍在运行的进程
+#[derive(Debug, Clone)]
+pub struct ProcessInstance {
+    pub process_id: String,
+    pub pid: u32,
+    /// 进程启动时间（平台相关的原始值），用于确认 PID 未被其他进程复用
+    pub start_time: u64,
+    /// 启动时间（秒）
+    pub started_at: i64,
+    /// 是否以脱离模式运行
+    pub detached: bool,
 }
 
 /// 进程信息（运行时状态）
@@ -63,13 +822,183 @@ pub struct ProcessInfo {
     pub args: Vec<String>,
     pub working_dir: String,
     pub source_path: Option<String>,
+    pub kind: ProcessKind,
     pub status: ProcessStatus,
     pub pid: Option<u32>,
-    pub auto_restart: bool,
+    /// 是否为应用重启后重新接管的进程（无法获取其输出和退出码）
+    pub adopted: bool,
+    pub restart_policy: RestartPolicy,
+    pub success_exit_codes: Vec<i32>,
     pub auto_start: bool,
     pub started_at: Option<i64>,
     pub created_at: i64,
     pub has_output: bool,
+    pub restart_delay_ms: u64,
+    pub restart_max_delay_ms: u64,
+    /// 连续自动重启次数
+    pub restart_attempts: u32,
+    /// 下一次自动重启的时间（毫秒时间戳）
+    pub next_restart_at: Option<i64>,
+    pub crash_loop_max_exits: u32,
+    pub crash_loop_window_secs: u64,
+    /// 崩溃循环隔离信息，存在时状态为 Error
+    pub quarantine: Option<QuarantineInfo>,
+    pub stop_signal: StopSignal,
+    pub stop_timeout_ms: u64,
+    /// 最近一次退出的信息，异常退出时状态为 Error
+    pub last_exit: Option<ExitInfo>,
+    pub health_check: Option<HealthCheckConfig>,
+    pub health: HealthState,
+    /// 连续健康检查失败次数
+    pub health_failures: u32,
+    pub readiness: Option<ReadinessConfig>,
+    pub depends_on: Vec<String>,
+    pub wait_for_dependencies: bool,
+    pub start_order: i32,
+    pub start_delay_ms: u64,
+    pub stale_cleanup: StaleCleanup,
+    pub survive_app_exit: bool,
+    /// 当前运行实例是否以脱离模式运行（退出应用时保持运行）
+    pub detached: bool,
+    pub pre_start: Vec<HookCommand>,
+    pub post_start: Vec<HookCommand>,
+    pub pre_stop: Vec<HookCommand>,
+    pub post_stop: Vec<HookCommand>,
+    pub use_pty: bool,
+    pub pty_size: PtySize,
+    pub resource_limits: ResourceLimits,
+    pub threshold_rules: Vec<ThresholdRule>,
+    pub task_timeout_ms: u64,
+    /// 运行中的进程最近一次采样的资源占用（仅 Linux）
+    pub metrics: Option<ProcessMetrics>,
+    /// 运行中的进程本次运行中触发的资源阈值规则（已结束的运行见运行记录）
+    pub threshold_triggers: Vec<ThresholdTrigger>,
+}
+
+/// 进程停止方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StopOutcome {
+    /// 进程在收到停止信号后自行退出
+    Signaled,
+    /// 等待超时后被强制结束
+    ForceKilled,
+    /// 停止前进程已经退出
+    AlreadyExited,
+}
+
+/// 停止进程结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StopResult {
+    pub id: String,
+    pub outcome: StopOutcome,
+    /// 发送的停止信号
+    pub signal: StopSignal,
+    /// 从发送信号到进程退出的耗时（毫秒）
+    pub elapsed_ms: u64,
+}
+
+/// 进程退出事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessExitedEvent {
+    pub id: String,
+    pub pid: u32,
+    pub exit: ExitInfo,
+}
+
+/// 进程因崩溃循环被隔离事件
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessQuarantinedEvent {
+    pub id: String,
+    pub reason: String,
+    pub exits: u32,
+    pub window_secs: u64,
+}
+
+/// 退出应用时停止进程的进度
+#[derive(Debug, Clone, Serialize)]
+pub struct ShutdownProgress {
+    /// 需要停止的进程总数
+    pub total: usize,
+    /// 已停止的进程数
+    pub stopped: usize,
+    /// 尚未停止的进程名称
+    pub pending: Vec<String>,
+    pub elapsed_ms: u64,
+    /// 总超时时间（毫秒），超时后剩余进程被强制结束
+    pub deadline_ms: u64,
+    /// 是否已全部停止
+    pub done: bool,
+}
+
+/// 进程生命周期事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ProcessEvent {
+    /// 正在启动
+    Starting,
+    /// 启动失败
+    StartFailed { error: String },
+    /// 子进程已创建
+    Started { pid: u32 },
+    /// 进程已就绪
+    Ready,
+    /// 正在停止
+    Stopping { signal: StopSignal },
+    /// 进程已退出
+    Exited {
+        pid: u32,
+        #[serde(flatten)]
+        exit: ExitInfo,
+    },
+    /// 将在延迟后自动重启
+    Restarting { attempt: u32, delay_ms: u64 },
+    /// 待执行的自动重启被取消
+    RestartCancelled,
+    /// 因崩溃循环被隔离
+    Quarantined {
+        reason: String,
+        exits: u32,
+        window_secs: u64,
+    },
+    /// 崩溃循环隔离被解除
+    QuarantineCleared,
+    /// 资源占用超过阈值规则
+    ThresholdExceeded {
+        rule: ThresholdRule,
+        /// 触发时的采样值
+        value: f64,
+    },
+    /// 健康状态变化
+    HealthChanged {
+        health: HealthState,
+        /// 最近一次检查失败的原因
+        message: Option<String>,
+    },
+    /// 配置被添加或修改
+    ConfigChanged,
+    /// 进程被删除
+    Removed,
+}
+
+impl From<ProcessQuarantinedEvent> for ProcessEvent {
+    fn from(event: ProcessQuarantinedEvent) -> Self {
+        ProcessEvent::Quarantined {
+            reason: event.reason,
+            exits: event.exits,
+            window_secs: event.window_secs,
+        }
+    }
+}
+
+/// 进程生命周期事件消息（`process-event` 事件的负载）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessEventMessage {
+    pub id: String,
+    /// 事件时间（毫秒）
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: ProcessEvent,
 }
 
 /// 进程输出事件
@@ -80,3 +1009,200 @@ pub struct ProcessOutputEvent {
     pub line: String,
     pub timestamp: i64,
 }
+
+/// 进程资源占用（进程及其所有子孙进程的合计）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProcessMetrics {
+    /// 采样时间（毫秒时间戳）
+    pub sampled_at: i64,
+    /// CPU 占用（百分比，100 表示占满一个核心）
+    pub cpu_percent: f64,
+    /// 常驻内存（字节）
+    pub rss_bytes: u64,
+    /// 线程数
+    pub threads: u32,
+    /// 进程数（包括子孙进程）
+    pub processes: u32,
+    /// 每秒从存储设备读取的字节数
+    pub read_bytes_per_sec: f64,
+    /// 每秒写入存储设备的字节数
+    pub write_bytes_per_sec: f64,
+}
+
+/// 资源占用历史的时间粒度
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum MetricsResolution {
+    /// 原始采样，保留 1 小时
+    #[serde(rename = "raw")]
+    Raw,
+    /// 1 分钟平均值，保留 1 天
+    #[serde(rename = "1m")]
+    Minute,
+    /// 15 分钟平均值，保留 30 天
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+}
+
+impl MetricsResolution {
+    /// 数据库中存储的粒度（秒），原始采样为 0
+    pub fn bucket_secs(&self) -> i64 {
+        match self {
+            MetricsResolution::Raw => 0,
+            MetricsResolution::Minute => 60,
+            MetricsResolution::FifteenMinutes => 15 * 60,
+        }
+    }
+
+    /// 保留时长（秒）
+    pub fn retention_secs(&self) -> i64 {
+        match self {
+            MetricsResolution::Raw => 60 * 60,
+            MetricsResolution::Minute => 24 * 60 * 60,
+            MetricsResolution::FifteenMinutes => 30 * 24 * 60 * 60,
+        }
+    }
+
+    /// 仍保留着 `from`（毫秒时间戳）之后数据的最细粒度
+    pub fn for_range(from: i64, now: i64) -> Self {
+        [MetricsResolution::Raw, MetricsResolution::Minute]
+            .into_iter()
+            .find(|resolution| from >= now - resolution.retention_secs() * 1000)
+            .unwrap_or(MetricsResolution::FifteenMinutes)
+    }
+}
+
+/// 进程资源占用事件
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessMetricsEvent {
+    pub id: String,
+    pub pid: u32,
+    pub metrics: ProcessMetrics,
+}
+
+/// 定时任务执行的操作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ScheduleAction {
+    /// 数据库中存储的操作名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleAction::Start => "start",
+            ScheduleAction::Stop => "stop",
+            ScheduleAction::Restart => "restart",
+        }
+    }
+
+    /// 从操作名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "start" => Some(ScheduleAction::Start),
+            "stop" => Some(ScheduleAction::Stop),
+            "restart" => Some(ScheduleAction::Restart),
+            _ => None,
+        }
+    }
+}
+
+/// 错过触发时间（应用未运行或系统休眠）时的处理策略
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MissedRunPolicy {
+    /// 补执行一次（错过多次也只执行一次）
+    #[default]
+    RunOnce,
+    /// 不补执行，仅记录为已跳过
+    Skip,
+}
+
+impl MissedRunPolicy {
+    /// 数据库中存储的策略名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MissedRunPolicy::RunOnce => "run-once",
+            MissedRunPolicy::Skip => "skip",
+        }
+    }
+
+    /// 从策略名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "run-once" => Some(MissedRunPolicy::RunOnce),
+            "skip" => Some(MissedRunPolicy::Skip),
+            _ => None,
+        }
+    }
+}
+
+/// 进程定时任务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessSchedule {
+    pub id: String,
+    pub process_id: String,
+    /// cron 表达式（分 时 日 月 周，本地时区）
+    pub cron: String,
+    pub action: ScheduleAction,
+    pub enabled: bool,
+    pub missed_run_policy: MissedRunPolicy,
+    /// 已检查到的时间（秒），此前的触发时间都已处理
+    pub last_checked_at: i64,
+    /// 下一次触发时间（秒），禁用时为 None
+    pub next_run_at: Option<i64>,
+    pub created_at: i64,
+}
+
+/// 定时任务单次触发的结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScheduleRunStatus {
+    /// 操作执行成功
+    Success,
+    /// 操作执行失败
+    Failed,
+    /// 未执行（错过触发时间且策略为跳过，或进程已处于目标状态）
+    Skipped,
+}
+
+impl ScheduleRunStatus {
+    /// 数据库中存储的结果名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduleRunStatus::Success => "success",
+            ScheduleRunStatus::Failed => "failed",
+            ScheduleRunStatus::Skipped => "skipped",
+        }
+    }
+
+    /// 从结果名称解析
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "success" => Some(ScheduleRunStatus::Success),
+            "failed" => Some(ScheduleRunStatus::Failed),
+            "skipped" => Some(ScheduleRunStatus::Skipped),
+            _ => None,
+        }
+    }
+}
+
+/// 定时任务触发记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    pub id: i64,
+    pub schedule_id: String,
+    pub process_id: String,
+    pub action: ScheduleAction,
+    /// 计划触发时间（秒）
+    pub scheduled_at: i64,
+    /// 实际执行时间（秒）
+    pub fired_at: i64,
+    /// 是否为错过触发时间后的补执行或跳过
+    pub missed: bool,
+    pub status: ScheduleRunStatus,
+    /// 失败或跳过的原因
+    pub message: Option<String>,
+}
@@ -0,0 +1,155 @@
+//! 进程退出等待
+//!
+//! 每个子进程启动后由一个独立线程阻塞等待其退出（不回收进程），
+//! 进程退出后立即交由守护逻辑回收、记录退出信息并按策略重启。
+//! 重新接管的进程不是本实例的子进程，Unix 下改为定期检查其是否仍在运行
+
+use std::process::Child;
+use std::thread;
+#[cfg(unix)]
+use std::time::Duration;
+use tauri::AppHandle;
+
+#[cfg(unix)]
+use super::handle::is_same_process;
+use super::state::ProcessManager;
+use super::supervisor::handle_exit;
+
+/// 检查重新接管的进程是否退出的间隔
+#[cfg(unix)]
+const ADOPTED_POLL_INTERVAL_MS: u64 = 1000;
+
+/// 等待子进程退出的句柄
+///
+/// 需在子进程注册到状态管理器之前创建，注册完成后调用 `start` 开始等待
+pub(super) struct ExitWaiter {
+    pid: u32,
+    /// 重新接管的进程的启动时间
+    #[cfg(unix)]
+    adopted_start_time: Option<u64>,
+    #[cfg(windows)]
+    handle: windows_sys::Win32::Foundation::HANDLE,
+}
+
+impl ExitWaiter {
+    /// 启动等待线程，进程退出后交由守护逻辑处理
+    pub(super) fn start(self, app: &AppHandle, manager: &ProcessManager, id: &str) {
+        let app = app.clone();
+        let manager = manager.clone();
+        let id = id.to_string();
+        thread::spawn(move || {
+            if self.wait() {
+                handle_exit(&app, &manager, &id, self.pid);
+            }
+        });
+    }
+}
+
+// 复制的进程句柄只在等待线程中使用
+#[cfg(windows)]
+unsafe impl Send for ExitWaiter {}
+
+#[cfg(unix)]
+impl ExitWaiter {
+    pub(super) fn new(child: &Child) -> Option<Self> {
+        Some(Self {
+            pid: child.id(),
+            adopted_start_time: None,
+        })
+    }
+
+    /// 等待重新接管的进程退出
+    pub(super) fn adopted(pid: u32, start_time: u64) -> Option<Self> {
+        Some(Self {
+            pid,
+            adopted_start_time: Some(start_time),
+        })
+    }
+
+    /// 阻塞直到子进程退出，使用 WNOWAIT 保留进程状态供 `Child::try_wait` 回收。
+    /// 进程已被其他线程回收时返回 false
+    fn wait(&self) -> bool {
+        if let Some(start_time) = self.adopted_start_time {
+            while is_same_process(self.pid, start_time) {
+                thread::sleep(Duration::from_millis(ADOPTED_POLL_INTERVAL_MS));
+            }
+            return true;
+        }
+        loop {
+            let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+            let ret = unsafe {
+                libc::waitid(
+                    libc::P_PID,
+                    self.pid as libc::id_t,
+                    &mut info,
+                    libc::WEXITED | libc::WNOWAIT,
+                )
+            };
+            if ret == 0 {
+                return true;
+            }
+            if std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted {
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+impl ExitWaiter {
+    /// 复制子进程句柄，使等待线程不依赖 `Child` 的生命周期
+    pub(super) fn new(child: &Child) -> Option<Self> {
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::Foundation::{DuplicateHandle, DUPLICATE_SAME_ACCESS};
+        use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+        let mut handle = std::ptr::null_mut();
+        let ok = unsafe {
+            DuplicateHandle(
+                GetCurrentProcess(),
+                child.as_raw_handle() as _,
+                GetCurrentProcess(),
+                &mut handle,
+                0,
+                0,
+                DUPLICATE_SAME_ACCESS,
+            )
+        };
+        if ok == 0 {
+            None
+        } else {
+            Some(Self {
+                pid: child.id(),
+                handle,
+            })
+        }
+    }
+
+    /// 打开重新接管的进程的句柄用于等待
+    pub(super) fn adopted(pid: u32, _start_time: u64) -> Option<Self> {
+        use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SYNCHRONIZE};
+
+        let handle = unsafe { OpenProcess(PROCESS_SYNCHRONIZE, 0, pid) };
+        if handle.is_null() {
+            None
+        } else {
+            Some(Self { pid, handle })
+        }
+    }
+
+    /// 阻塞直到子进程退出
+    fn wait(&self) -> bool {
+        use windows_sys::Win32::Foundation::WAIT_OBJECT_0;
+        use windows_sys::Win32::System::Threading::{WaitForSingleObject, INFINITE};
+        unsafe { WaitForSingleObject(self.handle, INFINITE) == WAIT_OBJECT_0 }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for ExitWaiter {
+    fn drop(&mut self) {
+        unsafe {
+            windows_sys::Win32::Foundation::CloseHandle(self.handle);
+        }
+    }
+}
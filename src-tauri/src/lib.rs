@@ -5,16 +5,58 @@ pub mod storage;
 pub mod system_theme;
 
 use core::{
-    add_process_fork, add_process_import, auto_start_processes_on_init, clear_process_output,
-    create_process_manager, get_process, get_process_output, kill_all_processes, list_processes,
-    remove_process, start_auto_start_processes, start_process, stop_process, update_process,
+    add_process_fork, add_process_import, add_process_schedule, auto_start_processes_on_init,
+    clear_process_output, clear_process_quarantine, create_process_manager, get_process,
+    get_process_metrics, get_process_output, list_process_runs, list_process_schedules,
+    list_processes, list_schedule_runs, remove_process, remove_process_schedule,
+    resize_process_pty, send_process_input, start_auto_start_processes, start_metrics_sampler,
+    start_process, start_scheduler, start_supervisor, stop_process, stop_process_with_dependents,
+    stop_processes_on_exit, update_process, update_process_schedule, watch_adopted_processes,
+    ProcessEventBus,
 };
+use std::sync::atomic::{AtomicBool, Ordering};
 use storage::{
     get_download_setting, init_db, init_process_manager_from_db, set_download_setting, DbState,
 };
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
-use tauri::{Manager, WindowEvent};
+use tauri::{AppHandle, Manager, WindowEvent};
+
+/// 系统托盘图标 ID
+const TRAY_ID: &str = "main";
+
+/// 是否正在退出应用（停止进程中）
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// 进程已停止，允许应用退出
+static SHUTDOWN_COMPLETE: AtomicBool = AtomicBool::new(false);
+
+/// 退出应用：在后台线程中停止子进程（脱离模式的进程保持运行），托盘提示显示停止进度，完成后退出
+///
+/// 托盘菜单、前端和系统发起的退出都经过这里，事件循环保持运行以显示停止进度，重复调用时忽略
+fn request_exit(app: &AppHandle, code: i32) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Some(manager) = app.try_state::<core::ProcessManager>() {
+            stop_processes_on_exit(&app, manager.inner(), |progress| {
+                if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                    let _ = tray.set_tooltip(Some(format!(
+                        "正在停止进程 ({}/{})",
+                        progress.stopped, progress.total
+                    )));
+                }
+            });
+        }
+        SHUTDOWN_COMPLETE.store(true, Ordering::SeqCst);
+        if code == tauri::RESTART_EXIT_CODE {
+            app.restart();
+        }
+        app.exit(code);
+    });
+}
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -67,17 +109,30 @@ pub fn run() {
             // 初始化进程管理器
             let process_manager = create_process_manager();
 
-            // 从数据库加载进程配置到内存
+            // 从数据库加载进程配置到内存，并重新接管上次启动且仍在运行的进程
             init_process_manager_from_db(&conn, &process_manager)
                 .expect("Failed to load process configs from database");
 
             app.manage(DbState(std::sync::Mutex::new(conn)));
             app.manage(process_manager.clone());
+            app.manage(ProcessEventBus::default());
 
-            // 启动设置为跟随应用启动的进程
+            // 跟踪重新接管的进程的退出和健康状态
             let app_handle = app.handle().clone();
+            watch_adopted_processes(&app_handle, &process_manager);
+
+            // 启动进程守护线程，负责回收退出的进程并执行自动重启
+            start_supervisor(&app_handle, &process_manager);
+
+            // 启动资源占用采样线程
+            start_metrics_sampler(&app_handle, &process_manager);
+
+            // 启动设置为跟随应用启动的进程
             auto_start_processes_on_init(&app_handle, &process_manager);
 
+            // 启动定时任务调度线程，并处理应用未运行期间错过的触发
+            start_scheduler(&app_handle, &process_manager);
+
             // 检查是否静默启动（通过命令行参数 --silent 触发，且用户启用了静默启动设置）
             let has_silent_arg = std::env::args().any(|arg| arg == "--silent");
             if has_silent_arg {
@@ -110,7 +165,7 @@ pub fn run() {
             let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
             let tray_menu = Menu::with_items(app, &[&show_item, &quit_item])?;
 
-            TrayIconBuilder::new()
+            TrayIconBuilder::with_id(TRAY_ID)
                 .icon(app.default_window_icon().unwrap().clone())
                 .tooltip("ServiceLauncher")
                 .menu(&tray_menu)
@@ -121,13 +176,7 @@ pub fn run() {
                             let _ = window.set_focus();
                         }
                     }
-                    "quit" => {
-                        // 退出前终止所有子进程
-                        if let Some(manager) = app.try_state::<core::ProcessManager>() {
-                            kill_all_processes(manager.inner());
-                        }
-                        app.exit(0);
-                    }
+                    "quit" => request_exit(app, 0),
                     _ => {}
                 })
                 .on_tray_icon_event(move |_tray, event| {
@@ -164,12 +213,24 @@ pub fn run() {
             remove_process,
             start_process,
             stop_process,
+            stop_process_with_dependents,
             list_processes,
             get_process,
             get_process_output,
             clear_process_output,
+            send_process_input,
+            resize_process_pty,
+            list_process_runs,
+            get_process_metrics,
             update_process,
             start_auto_start_processes,
+            clear_process_quarantine,
+            // 定时任务命令
+            list_process_schedules,
+            add_process_schedule,
+            update_process_schedule,
+            remove_process_schedule,
+            list_schedule_runs,
         ])
         .on_window_event(|window, event| {
             // 关闭窗口时隐藏到托盘而不是退出
@@ -180,12 +241,20 @@ pub fn run() {
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|app, event| {
-            if let tauri::RunEvent::Exit = event {
-                // 应用退出时终止所有子进程
+        .run(|app, event| match event {
+            // 进程停止前阻止退出，改为在后台停止进程后再退出
+            tauri::RunEvent::ExitRequested { api, code, .. }
+                if !SHUTDOWN_COMPLETE.load(Ordering::SeqCst) =>
+            {
+                api.prevent_exit();
+                request_exit(app, code.unwrap_or(0));
+            }
+            // 无法阻止的退出：仍然终止子进程（脱离模式的进程保持运行，下次启动时重新接管）
+            tauri::RunEvent::Exit if !SHUTDOWN_COMPLETE.load(Ordering::SeqCst) => {
                 if let Some(manager) = app.try_state::<core::ProcessManager>() {
-                    kill_all_processes(manager.inner());
+                    stop_processes_on_exit(app, manager.inner(), |_| {});
                 }
             }
+            _ => {}
         });
 }
@@ -46,22 +46,238 @@ pub fn init_db(app: AppHandle) -> Result<Connection, rusqlite::Error> {
         [],
     )?;
 
-    // 数据库迁移：检查并添加 command_type 列
-    let has_command_type: bool = conn
-        .query_row(
-            "SELECT COUNT(*) FROM pragma_table_info('processes') WHERE name='command_type'",
-            [],
-            |row| row.get::<_, i32>(0),
-        )
-        .map(|count| count > 0)
-        .unwrap_or(false);
+    // 数据库迁移：检查并添加新增的列
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "command_type",
+        "TEXT NOT NULL DEFAULT 'executable'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "restart_delay_ms",
+        "INTEGER NOT NULL DEFAULT 1000",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "restart_max_delay_ms",
+        "INTEGER NOT NULL DEFAULT 60000",
+    )?;
 
-    if !has_command_type {
+    // 数据库迁移：auto_restart 布尔值迁移为重启策略
+    // 旧的“自动重启”对应 on-failure（不限重试次数），不改变应用启动时的行为
+    if add_column_if_missing(
+        &conn,
+        "processes",
+        "restart_policy",
+        "TEXT NOT NULL DEFAULT 'no'",
+    )? {
         conn.execute(
-            "ALTER TABLE processes ADD COLUMN command_type TEXT NOT NULL DEFAULT 'executable'",
+            "UPDATE processes SET restart_policy = 'on-failure' WHERE auto_restart != 0",
             [],
         )?;
     }
+    add_column_if_missing(&conn, "processes", "restart_max_retries", "INTEGER")?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "success_exit_codes",
+        "TEXT NOT NULL DEFAULT '[]'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "user_stopped",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "crash_loop_max_exits",
+        "INTEGER NOT NULL DEFAULT 5",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "crash_loop_window_secs",
+        "INTEGER NOT NULL DEFAULT 60",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "stop_signal",
+        "TEXT NOT NULL DEFAULT 'sigterm'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "stop_timeout_ms",
+        "INTEGER NOT NULL DEFAULT 10000",
+    )?;
+    add_column_if_missing(&conn, "processes", "last_exit_code", "INTEGER")?;
+    add_column_if_missing(&conn, "processes", "last_exit_signal", "INTEGER")?;
+    add_column_if_missing(&conn, "processes", "last_exited_at", "INTEGER")?;
+    add_column_if_missing(&conn, "processes", "last_exit_reason", "TEXT")?;
+    add_column_if_missing(&conn, "processes", "health_check", "TEXT")?;
+    add_column_if_missing(&conn, "processes", "readiness", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "depends_on",
+        "TEXT NOT NULL DEFAULT '[]'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "wait_for_dependencies",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "start_order",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "start_delay_ms",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "kind",
+        "TEXT NOT NULL DEFAULT 'service'",
+    )?;
+    add_column_if_missing(&conn, "processes", "last_exit_duration_ms", "INTEGER")?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "stale_cleanup",
+        "TEXT NOT NULL DEFAULT 'none'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "survive_app_exit",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    for hooks in ["pre_start", "post_start", "pre_stop", "post_stop"] {
+        add_column_if_missing(&conn, "processes", hooks, "TEXT NOT NULL DEFAULT '[]'")?;
+    }
+    add_column_if_missing(&conn, "processes", "use_pty", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "pty_rows",
+        "INTEGER NOT NULL DEFAULT 24",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "pty_cols",
+        "INTEGER NOT NULL DEFAULT 80",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "resource_limits",
+        "TEXT NOT NULL DEFAULT '{}'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "threshold_rules",
+        "TEXT NOT NULL DEFAULT '[]'",
+    )?;
+    add_column_if_missing(
+        &conn,
+        "processes",
+        "task_timeout_ms",
+        "INTEGER NOT NULL DEFAULT 600000",
+    )?;
+
+    // Create process runs table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS process_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            process_id TEXT NOT NULL,
+            started_at INTEGER NOT NULL,
+            exited_at INTEGER NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            exit_code INTEGER,
+            exit_signal INTEGER,
+            reason TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create threshold triggers table
+    // 资源阈值规则的触发记录，按触发时间归入对应的运行记录
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS threshold_triggers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            process_id TEXT NOT NULL,
+            triggered_at INTEGER NOT NULL,
+            rule TEXT NOT NULL,
+            value REAL NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create process instances table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS process_instances (
+            process_id TEXT PRIMARY KEY NOT NULL,
+            pid INTEGER NOT NULL,
+            start_time INTEGER NOT NULL,
+            started_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    add_column_if_missing(
+        &conn,
+        "process_instances",
+        "detached",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+
+    // Create process schedules table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS process_schedules (
+            id TEXT PRIMARY KEY NOT NULL,
+            process_id TEXT NOT NULL,
+            cron TEXT NOT NULL,
+            action TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            missed_run_policy TEXT NOT NULL DEFAULT 'run-once',
+            last_checked_at INTEGER NOT NULL,
+            created_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create schedule runs table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schedule_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            schedule_id TEXT NOT NULL,
+            process_id TEXT NOT NULL,
+            action TEXT NOT NULL,
+            scheduled_at INTEGER NOT NULL,
+            fired_at INTEGER NOT NULL,
+            missed INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL,
+            message TEXT
+        )",
+        [],
+    )?;
+
+    // Create process metrics table
+    create_process_metrics_table(&conn)?;
 
     // 初始化默认设置（仅当设置不存在时）
     init_default_settings(&conn, &app);
@@ -69,6 +285,61 @@ pub fn init_db(app: AppHandle) -> Result<Connection, rusqlite::Error> {
     Ok(conn)
 }
 
+/// 创建资源占用历史表
+///
+/// resolution 为汇总粒度（秒），原始采样为 0；汇总行的各项为平均值，samples 为原始采样数
+fn create_process_metrics_table(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS process_metrics (
+            process_id TEXT NOT NULL,
+            resolution INTEGER NOT NULL,
+            timestamp INTEGER NOT NULL,
+            cpu_percent REAL NOT NULL,
+            rss_bytes INTEGER NOT NULL,
+            threads INTEGER NOT NULL,
+            processes INTEGER NOT NULL,
+            read_bytes_per_sec REAL NOT NULL,
+            write_bytes_per_sec REAL NOT NULL,
+            samples INTEGER NOT NULL DEFAULT 1,
+            PRIMARY KEY (process_id, resolution, timestamp)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// 数据库迁移：列不存在时添加该列
+///
+/// 返回是否新增了该列
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<bool, rusqlite::Error> {
+    let exists: bool = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1",
+                table
+            ),
+            params![column],
+            |row| row.get::<_, i32>(0),
+        )
+        .map(|count| count > 0)
+        .unwrap_or(false);
+
+    if exists {
+        return Ok(false);
+    }
+
+    conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        [],
+    )?;
+    Ok(true)
+}
+
 /// 初始化默认设置
 /// 仅当设置不存在时才设置默认值
 fn init_default_settings(conn: &Connection, app: &AppHandle) {
@@ -143,7 +414,12 @@ pub fn set_download_setting(
 
 // ============ Process Config CRUD Operations ============
 
-use crate::core::{CommandType, ProcessConfig, ProcessMode};
+use crate::core::{
+    CommandType, ExitInfo, ExitReason, MetricsResolution, MissedRunPolicy, ProcessConfig,
+    ProcessInstance, ProcessKind, ProcessMetrics, ProcessMetricsEvent, ProcessMode, ProcessRun,
+    ProcessSchedule, PtySize, RestartPolicy, ScheduleAction, ScheduleRun, ScheduleRunStatus,
+    StaleCleanup, StopSignal, ThresholdTrigger,
+};
 
 /// 保存进程配置到数据库
 pub fn save_process_config(conn: &Connection, config: &ProcessConfig) -> Result<(), String> {
@@ -157,17 +433,72 @@ pub fn save_process_config(conn: &Connection, config: &ProcessConfig) -> Result<
     };
     let args_json = serde_json::to_string(&config.args).map_err(|e| e.to_string())?;
     let env_json = serde_json::to_string(&config.env).map_err(|e| e.to_string())?;
+    let success_exit_codes_json =
+        serde_json::to_string(&config.success_exit_codes).map_err(|e| e.to_string())?;
+    let depends_on_json = serde_json::to_string(&config.depends_on).map_err(|e| e.to_string())?;
+    let pre_start_json = serde_json::to_string(&config.pre_start).map_err(|e| e.to_string())?;
+    let post_start_json = serde_json::to_string(&config.post_start).map_err(|e| e.to_string())?;
+    let pre_stop_json = serde_json::to_string(&config.pre_stop).map_err(|e| e.to_string())?;
+    let post_stop_json = serde_json::to_string(&config.post_stop).map_err(|e| e.to_string())?;
+    let resource_limits_json =
+        serde_json::to_string(&config.resource_limits).map_err(|e| e.to_string())?;
+    let threshold_rules_json =
+        serde_json::to_string(&config.threshold_rules).map_err(|e| e.to_string())?;
+    let restart_max_retries = match config.restart_policy {
+        RestartPolicy::OnFailure { max_retries } => max_retries,
+        _ => None,
+    };
+    let health_check_json = config
+        .health_check
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let readiness_json = config
+        .readiness
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| e.to_string())?;
 
     conn.execute(
-        "INSERT INTO processes (id, name, mode, command_type, command, args, working_dir, source_path, env, auto_restart, auto_start, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+        "INSERT INTO processes (id, name, mode, command_type, command, args, working_dir, source_path, env, auto_restart, auto_start, created_at, restart_delay_ms, restart_max_delay_ms, restart_policy, restart_max_retries, success_exit_codes, crash_loop_max_exits, crash_loop_window_secs, stop_signal, stop_timeout_ms, health_check, readiness, depends_on, wait_for_dependencies, start_order, start_delay_ms, kind, stale_cleanup, survive_app_exit, pre_start, post_start, pre_stop, post_stop, use_pty, pty_rows, pty_cols, resource_limits, threshold_rules, task_timeout_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40)
          ON CONFLICT(id) DO UPDATE SET
             name = excluded.name,
             command_type = excluded.command_type,
             command = excluded.command,
             args = excluded.args,
             auto_restart = excluded.auto_restart,
-            auto_start = excluded.auto_start",
+            auto_start = excluded.auto_start,
+            restart_delay_ms = excluded.restart_delay_ms,
+            restart_max_delay_ms = excluded.restart_max_delay_ms,
+            restart_policy = excluded.restart_policy,
+            restart_max_retries = excluded.restart_max_retries,
+            success_exit_codes = excluded.success_exit_codes,
+            crash_loop_max_exits = excluded.crash_loop_max_exits,
+            crash_loop_window_secs = excluded.crash_loop_window_secs,
+            stop_signal = excluded.stop_signal,
+            stop_timeout_ms = excluded.stop_timeout_ms,
+            health_check = excluded.health_check,
+            readiness = excluded.readiness,
+            depends_on = excluded.depends_on,
+            wait_for_dependencies = excluded.wait_for_dependencies,
+            start_order = excluded.start_order,
+            start_delay_ms = excluded.start_delay_ms,
+            kind = excluded.kind,
+            stale_cleanup = excluded.stale_cleanup,
+            survive_app_exit = excluded.survive_app_exit,
+            pre_start = excluded.pre_start,
+            post_start = excluded.post_start,
+            pre_stop = excluded.pre_stop,
+            post_stop = excluded.post_stop,
+            use_pty = excluded.use_pty,
+            pty_rows = excluded.pty_rows,
+            pty_cols = excluded.pty_cols,
+            resource_limits = excluded.resource_limits,
+            threshold_rules = excluded.threshold_rules,
+            task_timeout_ms = excluded.task_timeout_ms",
         params![
             config.id,
             config.name,
@@ -178,24 +509,495 @@ pub fn save_process_config(conn: &Connection, config: &ProcessConfig) -> Result<
             config.working_dir,
             config.source_path,
             env_json,
-            config.auto_restart as i32,
+            // auto_restart 列保留用于兼容旧版本
+            config.restart_policy.is_enabled() as i32,
             config.auto_start as i32,
-            config.created_at
+            config.created_at,
+            config.restart_delay_ms as i64,
+            config.restart_max_delay_ms as i64,
+            config.restart_policy.as_str(),
+            restart_max_retries,
+            success_exit_codes_json,
+            config.crash_loop_max_exits,
+            config.crash_loop_window_secs as i64,
+            config.stop_signal.as_str(),
+            config.stop_timeout_ms as i64,
+            health_check_json,
+            readiness_json,
+            depends_on_json,
+            config.wait_for_dependencies as i32,
+            config.start_order,
+            config.start_delay_ms as i64,
+            config.kind.as_str(),
+            config.stale_cleanup.as_str(),
+            config.survive_app_exit as i32,
+            pre_start_json,
+            post_start_json,
+            pre_stop_json,
+            post_stop_json,
+            config.use_pty as i32,
+            config.pty_size.rows,
+            config.pty_size.cols,
+            resource_limits_json,
+            threshold_rules_json,
+            config.task_timeout_ms as i64
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// 记录进程是否被用户手动停止（用于 unless-stopped 重启策略）
+pub fn set_process_user_stopped(conn: &Connection, id: &str, stopped: bool) -> Result<(), String> {
+    conn.execute(
+        "UPDATE processes SET user_stopped = ?1 WHERE id = ?2",
+        params![stopped as i32, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 加载被用户手动停止的进程 ID
+pub fn load_user_stopped_ids(conn: &Connection) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id FROM processes WHERE user_stopped != 0")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+    ids.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 退出应用时保持所有进程运行的设置项
+pub const SURVIVE_APP_EXIT_KEY: &str = "survive_app_exit";
+
+/// 加载是否在退出应用时保持所有进程运行，未设置时为 false
+pub fn load_survive_app_exit(conn: &Connection) -> bool {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![SURVIVE_APP_EXIT_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|value| value == "true")
+    .unwrap_or(false)
+}
+
+/// 全局自动启动延迟的设置项（秒）
+pub const AUTO_START_DELAY_KEY: &str = "auto_start_delay_secs";
+
+/// 加载应用启动后延迟自动启动进程的秒数，未设置时为 0
+pub fn load_auto_start_delay_secs(conn: &Connection) -> u64 {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![AUTO_START_DELAY_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.trim().parse().ok())
+    .unwrap_or(0)
+}
+
+/// 退出应用时停止所有进程的总超时时间设置项（秒）
+pub const SHUTDOWN_TIMEOUT_KEY: &str = "shutdown_timeout_secs";
+
+/// 未设置时退出应用停止所有进程的总超时时间（秒）
+pub const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+/// 加载退出应用时停止所有进程的总超时时间（秒），未设置时为 30
+pub fn load_shutdown_timeout_secs(conn: &Connection) -> u64 {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![SHUTDOWN_TIMEOUT_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|value| value.trim().parse().ok())
+    .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS)
+}
+
+/// 保存进程最近一次退出的信息
+pub fn save_process_exit(conn: &Connection, id: &str, info: &ExitInfo) -> Result<(), String> {
+    conn.execute(
+        "UPDATE processes SET last_exit_code = ?2, last_exit_signal = ?3, last_exited_at = ?4, last_exit_reason = ?5,
+            last_exit_duration_ms = ?6
+         WHERE id = ?1",
+        params![
+            id,
+            info.code,
+            info.signal,
+            info.exited_at,
+            info.reason.as_str(),
+            info.duration_ms.map(|ms| ms as i64)
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 加载各进程最近一次退出的信息
+pub fn load_process_exits(conn: &Connection) -> Result<Vec<(String, ExitInfo)>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, last_exit_code, last_exit_signal, last_exited_at, last_exit_reason, last_exit_duration_ms
+             FROM processes WHERE last_exit_reason IS NOT NULL",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let id: String = row.get(0)?;
+            let code: Option<i32> = row.get(1)?;
+            let signal: Option<i32> = row.get(2)?;
+            let exited_at: Option<i64> = row.get(3)?;
+            let reason_str: String = row.get(4)?;
+            let duration_ms: Option<i64> = row.get(5)?;
+            Ok((id, code, signal, exited_at, reason_str, duration_ms))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut exits = Vec::new();
+    for row in rows {
+        let (id, code, signal, exited_at, reason_str, duration_ms) =
+            row.map_err(|e| e.to_string())?;
+        if let Some(reason) = ExitReason::parse(&reason_str) {
+            let info = ExitInfo {
+                code,
+                signal,
+                exited_at: exited_at.unwrap_or(0),
+                reason,
+                duration_ms: duration_ms.map(|ms| ms.max(0) as u64),
+            };
+            exits.push((id, info));
+        }
+    }
+    Ok(exits)
+}
+
+/// 每个进程保留的运行记录数量
+const MAX_PROCESS_RUNS: i64 = 20;
+
+/// 保存进程的一次运行记录，只保留最近的记录
+pub fn save_process_run(conn: &Connection, id: &str, info: &ExitInfo) -> Result<(), String> {
+    let duration_ms = info.duration_ms.unwrap_or(0) as i64;
+    conn.execute(
+        "INSERT INTO process_runs (process_id, started_at, exited_at, duration_ms, exit_code, exit_signal, reason)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            id,
+            info.exited_at - duration_ms / 1000,
+            info.exited_at,
+            duration_ms,
+            info.code,
+            info.signal,
+            info.reason.as_str()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM process_runs WHERE process_id = ?1 AND id NOT IN (
+            SELECT id FROM process_runs WHERE process_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        params![id, MAX_PROCESS_RUNS],
+    )
+    .map_err(|e| e.to_string())?;
+    // 删除已不属于任何保留的运行记录的触发记录
+    conn.execute(
+        "DELETE FROM threshold_triggers WHERE process_id = ?1 AND triggered_at < (
+            SELECT MIN(started_at) FROM process_runs WHERE process_id = ?1
+         )",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 保存资源阈值规则的一次触发
+pub fn save_threshold_trigger(
+    conn: &Connection,
+    id: &str,
+    trigger: &ThresholdTrigger,
+) -> Result<(), String> {
+    let rule_json = serde_json::to_string(&trigger.rule).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO threshold_triggers (process_id, triggered_at, rule, value) VALUES (?1, ?2, ?3, ?4)",
+        params![id, trigger.triggered_at, rule_json, trigger.value],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 加载进程的资源阈值规则触发记录（按时间顺序）
+fn load_threshold_triggers(conn: &Connection, id: &str) -> Result<Vec<ThresholdTrigger>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT triggered_at, rule, value FROM threshold_triggers
+             WHERE process_id = ?1 ORDER BY triggered_at, id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            let triggered_at: i64 = row.get(0)?;
+            let rule_json: String = row.get(1)?;
+            let value: f64 = row.get(2)?;
+            Ok((triggered_at, rule_json, value))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut triggers = Vec::new();
+    for row in rows {
+        let (triggered_at, rule_json, value) = row.map_err(|e| e.to_string())?;
+        // 跳过无法解析的记录
+        if let Ok(rule) = serde_json::from_str(&rule_json) {
+            triggers.push(ThresholdTrigger {
+                triggered_at,
+                rule,
+                value,
+            });
+        }
+    }
+    Ok(triggers)
+}
+
+/// 加载进程最近的运行记录（最新的在前），附带每次运行中触发的资源阈值规则
+pub fn load_process_runs(conn: &Connection, id: &str) -> Result<Vec<ProcessRun>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, process_id, started_at, exited_at, duration_ms, exit_code, exit_signal, reason
+             FROM process_runs WHERE process_id = ?1 ORDER BY id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            let reason: String = row.get(7)?;
+            Ok(ProcessRun {
+                id: row.get(0)?,
+                process_id: row.get(1)?,
+                started_at: row.get(2)?,
+                exited_at: row.get(3)?,
+                duration_ms: row.get::<_, i64>(4)?.max(0) as u64,
+                code: row.get(5)?,
+                signal: row.get(6)?,
+                reason: ExitReason::parse(&reason).unwrap_or(ExitReason::Crashed),
+                threshold_triggers: Vec::new(),
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    let mut runs = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let triggers = load_threshold_triggers(conn, id)?;
+    for run in &mut runs {
+        run.threshold_triggers = triggers
+            .iter()
+            .filter(|t| t.triggered_at >= run.started_at && t.triggered_at <= run.exited_at)
+            .cloned()
+            .collect();
+    }
+    Ok(runs)
+}
+
+/// 保存一次采样的资源占用（原始数据）
+pub fn save_process_metrics(
+    conn: &Connection,
+    samples: &[ProcessMetricsEvent],
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "INSERT OR REPLACE INTO process_metrics (process_id, resolution, timestamp, cpu_percent, rss_bytes, threads, processes, read_bytes_per_sec, write_bytes_per_sec)
+             VALUES (?1, 0, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )
+        .map_err(|e| e.to_string())?;
+    for sample in samples {
+        let metrics = &sample.metrics;
+        stmt.execute(params![
+            sample.id,
+            metrics.sampled_at,
+            metrics.cpu_percent,
+            metrics.rss_bytes as i64,
+            metrics.threads,
+            metrics.processes,
+            metrics.read_bytes_per_sec,
+            metrics.write_bytes_per_sec
+        ])
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 汇总资源占用历史并删除过期数据
+///
+/// 原始采样汇总为 1 分钟平均值，1 分钟平均值再汇总为 15 分钟平均值（按采样数加权）。
+/// 只汇总已结束的时间段，每个进程从上次汇总的最后一个时间段之后开始
+pub fn compact_process_metrics(conn: &Connection, now_ms: i64) -> Result<(), String> {
+    let levels = [
+        (MetricsResolution::Raw, MetricsResolution::Minute),
+        (MetricsResolution::Minute, MetricsResolution::FifteenMinutes),
+    ];
+    for (source, target) in levels {
+        let bucket_ms = target.bucket_secs() * 1000;
+        conn.execute(
+            "INSERT OR REPLACE INTO process_metrics (process_id, resolution, timestamp, cpu_percent, rss_bytes, threads, processes, read_bytes_per_sec, write_bytes_per_sec, samples)
+             SELECT process_id, ?2, (timestamp / ?3) * ?3 AS bucket,
+                    SUM(cpu_percent * samples) / SUM(samples),
+                    CAST(SUM(rss_bytes * samples) / SUM(samples) AS INTEGER),
+                    CAST(SUM(threads * samples) / SUM(samples) AS INTEGER),
+                    CAST(SUM(processes * samples) / SUM(samples) AS INTEGER),
+                    SUM(read_bytes_per_sec * samples) / SUM(samples),
+                    SUM(write_bytes_per_sec * samples) / SUM(samples),
+                    SUM(samples)
+             FROM process_metrics AS m
+             WHERE resolution = ?1 AND timestamp < ?4
+               AND timestamp >= COALESCE((
+                   SELECT MAX(timestamp) + ?3 FROM process_metrics
+                   WHERE process_id = m.process_id AND resolution = ?2
+               ), 0)
+             GROUP BY process_id, bucket",
+            params![
+                source.bucket_secs(),
+                target.bucket_secs(),
+                bucket_ms,
+                now_ms - now_ms % bucket_ms
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    for resolution in [
+        MetricsResolution::Raw,
+        MetricsResolution::Minute,
+        MetricsResolution::FifteenMinutes,
+    ] {
+        conn.execute(
+            "DELETE FROM process_metrics WHERE resolution = ?1 AND timestamp < ?2",
+            params![
+                resolution.bucket_secs(),
+                now_ms - resolution.retention_secs() * 1000
+            ],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 加载进程在时间范围内（毫秒时间戳，包含两端）的资源占用历史
+pub fn load_process_metrics(
+    conn: &Connection,
+    id: &str,
+    resolution: MetricsResolution,
+    from: i64,
+    to: i64,
+) -> Result<Vec<ProcessMetrics>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT timestamp, cpu_percent, rss_bytes, threads, processes, read_bytes_per_sec, write_bytes_per_sec
+             FROM process_metrics
+             WHERE process_id = ?1 AND resolution = ?2 AND timestamp BETWEEN ?3 AND ?4
+             ORDER BY timestamp",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![id, resolution.bucket_secs(), from, to], |row| {
+            Ok(ProcessMetrics {
+                sampled_at: row.get(0)?,
+                cpu_percent: row.get(1)?,
+                rss_bytes: row.get::<_, i64>(2)?.max(0) as u64,
+                threads: row.get(3)?,
+                processes: row.get(4)?,
+                read_bytes_per_sec: row.get(5)?,
+                write_bytes_per_sec: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 保存运行实例记录（每个进程只保留当前运行的实例）
+pub fn save_process_instance(conn: &Connection, instance: &ProcessInstance) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO process_instances (process_id, pid, start_time, started_at, detached)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            instance.process_id,
+            instance.pid,
+            instance.start_time as i64,
+            instance.started_at,
+            instance.detached as i32
         ],
     )
     .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
+/// 删除运行实例记录，记录已被新实例替换（PID 不同）时保留
+pub fn delete_process_instance(conn: &Connection, id: &str, pid: u32) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM process_instances WHERE process_id = ?1 AND pid = ?2",
+        params![id, pid],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
+/// 加载所有运行实例记录
+pub fn load_process_instances(conn: &Connection) -> Result<Vec<ProcessInstance>, String> {
+    let mut stmt = conn
+        .prepare("SELECT process_id, pid, start_time, started_at, detached FROM process_instances")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(ProcessInstance {
+                process_id: row.get(0)?,
+                pid: row.get(1)?,
+                start_time: row.get::<_, i64>(2)? as u64,
+                started_at: row.get(3)?,
+                detached: row.get::<_, i32>(4)? != 0,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
 /// 从数据库删除进程配置
 pub fn delete_process_config(conn: &Connection, id: &str) -> Result<(), String> {
     conn.execute("DELETE FROM processes WHERE id = ?1", params![id])
         .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM process_schedules WHERE process_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM schedule_runs WHERE process_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM process_runs WHERE process_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM process_instances WHERE process_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM process_metrics WHERE process_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM threshold_triggers WHERE process_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
-/// 初始化进程管理器：从数据库加载所有进程配置到内存
+/// 初始化进程管理器：从数据库加载所有进程配置到内存，并重新接管仍在运行的进程
 pub fn init_process_manager_from_db(
     conn: &Connection,
     manager: &crate::core::ProcessManager,
@@ -211,6 +1013,33 @@ pub fn init_process_manager_from_db(
             .insert(id, crate::core::ProcessOutput::default());
     }
 
+    // 恢复上次运行时记录的退出信息
+    for (id, info) in load_process_exits(conn)? {
+        if state.configs.contains_key(&id) {
+            state.runtime.entry(id).or_default().last_exit = Some(info);
+        }
+    }
+
+    // 重新接管上次运行时启动、仍在运行的进程，删除已失效的实例记录
+    let instances = load_process_instances(conn)?;
+    for instance in crate::core::adopt_running_processes(&mut state, instances) {
+        delete_process_instance(conn, &instance.process_id, instance.pid)?;
+    }
+
+    // 恢复重新接管的进程本次运行中触发的资源阈值规则
+    let adopted: Vec<(String, i64)> = state
+        .processes
+        .iter()
+        .map(|(id, running)| (id.clone(), running.started_at))
+        .collect();
+    for (id, started_at) in adopted {
+        let triggers = load_threshold_triggers(conn, &id)?
+            .into_iter()
+            .filter(|t| t.triggered_at >= started_at)
+            .collect();
+        state.runtime.entry(id).or_default().threshold_triggers = triggers;
+    }
+
     Ok(())
 }
 
@@ -218,7 +1047,12 @@ pub fn init_process_manager_from_db(
 pub fn load_all_process_configs(conn: &Connection) -> Result<Vec<ProcessConfig>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, name, mode, command_type, command, args, working_dir, source_path, env, auto_restart, auto_start, created_at
+            "SELECT id, name, mode, command_type, command, args, working_dir, source_path, env, auto_start, created_at,
+                    restart_delay_ms, restart_max_delay_ms, restart_policy, restart_max_retries, success_exit_codes,
+                    crash_loop_max_exits, crash_loop_window_secs, stop_signal, stop_timeout_ms, health_check, readiness,
+                    depends_on, wait_for_dependencies, start_order, start_delay_ms, kind, stale_cleanup,
+                    survive_app_exit, pre_start, post_start, pre_stop, post_stop, use_pty, pty_rows, pty_cols,
+                    resource_limits, threshold_rules, task_timeout_ms
              FROM processes ORDER BY created_at DESC",
         )
         .map_err(|e| e.to_string())?;
@@ -234,9 +1068,36 @@ pub fn load_all_process_configs(conn: &Connection) -> Result<Vec<ProcessConfig>,
             let working_dir: String = row.get(6)?;
             let source_path: Option<String> = row.get(7)?;
             let env_json: String = row.get(8)?;
-            let auto_restart: i32 = row.get(9)?;
-            let auto_start: i32 = row.get(10)?;
-            let created_at: i64 = row.get(11)?;
+            let auto_start: i32 = row.get(9)?;
+            let created_at: i64 = row.get(10)?;
+            let restart_delay_ms: i64 = row.get(11)?;
+            let restart_max_delay_ms: i64 = row.get(12)?;
+            let restart_policy_str: String = row.get(13)?;
+            let restart_max_retries: Option<u32> = row.get(14)?;
+            let success_exit_codes_json: String = row.get(15)?;
+            let crash_loop_max_exits: u32 = row.get(16)?;
+            let crash_loop_window_secs: i64 = row.get(17)?;
+            let stop_signal_str: String = row.get(18)?;
+            let stop_timeout_ms: i64 = row.get(19)?;
+            let health_check_json: Option<String> = row.get(20)?;
+            let readiness_json: Option<String> = row.get(21)?;
+            let depends_on_json: String = row.get(22)?;
+            let wait_for_dependencies: i32 = row.get(23)?;
+            let start_order: i32 = row.get(24)?;
+            let start_delay_ms: i64 = row.get(25)?;
+            let kind_str: String = row.get(26)?;
+            let stale_cleanup_str: String = row.get(27)?;
+            let survive_app_exit: i32 = row.get(28)?;
+            let pre_start_json: String = row.get(29)?;
+            let post_start_json: String = row.get(30)?;
+            let pre_stop_json: String = row.get(31)?;
+            let post_stop_json: String = row.get(32)?;
+            let use_pty: i32 = row.get(33)?;
+            let pty_rows: u16 = row.get(34)?;
+            let pty_cols: u16 = row.get(35)?;
+            let resource_limits_json: String = row.get(36)?;
+            let threshold_rules_json: String = row.get(37)?;
+            let task_timeout_ms: i64 = row.get(38)?;
 
             let mode = if mode_str == "fork" {
                 ProcessMode::Fork
@@ -253,6 +1114,12 @@ pub fn load_all_process_configs(conn: &Connection) -> Result<Vec<ProcessConfig>,
             let args: Vec<String> = serde_json::from_str(&args_json).unwrap_or_default();
             let env: std::collections::HashMap<String, String> =
                 serde_json::from_str(&env_json).unwrap_or_default();
+            let success_exit_codes: Vec<i32> =
+                serde_json::from_str(&success_exit_codes_json).unwrap_or_default();
+            let health_check = health_check_json.and_then(|json| serde_json::from_str(&json).ok());
+            let readiness = readiness_json.and_then(|json| serde_json::from_str(&json).ok());
+            let depends_on: Vec<String> =
+                serde_json::from_str(&depends_on_json).unwrap_or_default();
 
             Ok(ProcessConfig {
                 id,
@@ -264,9 +1131,37 @@ pub fn load_all_process_configs(conn: &Connection) -> Result<Vec<ProcessConfig>,
                 working_dir,
                 source_path,
                 env,
-                auto_restart: auto_restart != 0,
+                kind: ProcessKind::parse(&kind_str),
+                restart_policy: RestartPolicy::from_parts(&restart_policy_str, restart_max_retries),
+                success_exit_codes,
                 auto_start: auto_start != 0,
                 created_at,
+                restart_delay_ms: restart_delay_ms.max(1) as u64,
+                restart_max_delay_ms: restart_max_delay_ms.max(1) as u64,
+                crash_loop_max_exits,
+                crash_loop_window_secs: crash_loop_window_secs.max(1) as u64,
+                stop_signal: StopSignal::parse(&stop_signal_str),
+                stop_timeout_ms: stop_timeout_ms.max(0) as u64,
+                health_check,
+                readiness,
+                depends_on,
+                wait_for_dependencies: wait_for_dependencies != 0,
+                start_order,
+                start_delay_ms: start_delay_ms.max(0) as u64,
+                stale_cleanup: StaleCleanup::parse(&stale_cleanup_str),
+                survive_app_exit: survive_app_exit != 0,
+                pre_start: serde_json::from_str(&pre_start_json).unwrap_or_default(),
+                post_start: serde_json::from_str(&post_start_json).unwrap_or_default(),
+                pre_stop: serde_json::from_str(&pre_stop_json).unwrap_or_default(),
+                post_stop: serde_json::from_str(&post_stop_json).unwrap_or_default(),
+                use_pty: use_pty != 0,
+                pty_size: PtySize {
+                    rows: pty_rows.max(1),
+                    cols: pty_cols.max(1),
+                },
+                resource_limits: serde_json::from_str(&resource_limits_json).unwrap_or_default(),
+                threshold_rules: serde_json::from_str(&threshold_rules_json).unwrap_or_default(),
+                task_timeout_ms: task_timeout_ms.max(1) as u64,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -275,3 +1170,278 @@ pub fn load_all_process_configs(conn: &Connection) -> Result<Vec<ProcessConfig>,
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())
 }
+
+// ============ Process Schedule Operations ============
+
+/// 每个定时任务保留的触发记录数量
+const MAX_SCHEDULE_RUNS: i64 = 100;
+
+/// 保存定时任务
+pub fn save_process_schedule(conn: &Connection, schedule: &ProcessSchedule) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO process_schedules (id, process_id, cron, action, enabled, missed_run_policy, last_checked_at, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(id) DO UPDATE SET
+            cron = excluded.cron,
+            action = excluded.action,
+            enabled = excluded.enabled,
+            missed_run_policy = excluded.missed_run_policy,
+            last_checked_at = excluded.last_checked_at",
+        params![
+            schedule.id,
+            schedule.process_id,
+            schedule.cron,
+            schedule.action.as_str(),
+            schedule.enabled as i32,
+            schedule.missed_run_policy.as_str(),
+            schedule.last_checked_at,
+            schedule.created_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 加载所有定时任务
+pub fn load_process_schedules(conn: &Connection) -> Result<Vec<ProcessSchedule>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, process_id, cron, action, enabled, missed_run_policy, last_checked_at, created_at
+             FROM process_schedules ORDER BY created_at",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            let action: String = row.get(3)?;
+            let missed_run_policy: String = row.get(5)?;
+            Ok(ProcessSchedule {
+                id: row.get(0)?,
+                process_id: row.get(1)?,
+                cron: row.get(2)?,
+                action: ScheduleAction::parse(&action).unwrap_or(ScheduleAction::Start),
+                enabled: row.get::<_, i32>(4)? != 0,
+                missed_run_policy: MissedRunPolicy::parse(&missed_run_policy).unwrap_or_default(),
+                last_checked_at: row.get(6)?,
+                next_run_at: None,
+                created_at: row.get(7)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 更新定时任务已检查到的时间
+pub fn set_schedule_checked_at(conn: &Connection, id: &str, checked_at: i64) -> Result<(), String> {
+    conn.execute(
+        "UPDATE process_schedules SET last_checked_at = ?1 WHERE id = ?2",
+        params![checked_at, id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 删除定时任务及其触发记录
+pub fn delete_process_schedule(conn: &Connection, id: &str) -> Result<(), String> {
+    conn.execute("DELETE FROM process_schedules WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM schedule_runs WHERE schedule_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 保存定时任务的触发记录，只保留最近的记录
+pub fn save_schedule_run(conn: &Connection, run: &ScheduleRun) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO schedule_runs (schedule_id, process_id, action, scheduled_at, fired_at, missed, status, message)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            run.schedule_id,
+            run.process_id,
+            run.action.as_str(),
+            run.scheduled_at,
+            run.fired_at,
+            run.missed as i32,
+            run.status.as_str(),
+            run.message
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM schedule_runs WHERE schedule_id = ?1 AND id NOT IN (
+            SELECT id FROM schedule_runs WHERE schedule_id = ?1 ORDER BY id DESC LIMIT ?2
+         )",
+        params![run.schedule_id, MAX_SCHEDULE_RUNS],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 加载定时任务的触发记录（最新的在前），可按进程或定时任务筛选
+pub fn load_schedule_runs(
+    conn: &Connection,
+    process_id: Option<&str>,
+    schedule_id: Option<&str>,
+    limit: u32,
+) -> Result<Vec<ScheduleRun>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, schedule_id, process_id, action, scheduled_at, fired_at, missed, status, message
+             FROM schedule_runs
+             WHERE (?1 IS NULL OR process_id = ?1) AND (?2 IS NULL OR schedule_id = ?2)
+             ORDER BY id DESC LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![process_id, schedule_id, limit], |row| {
+            let action: String = row.get(3)?;
+            let status: String = row.get(7)?;
+            Ok(ScheduleRun {
+                id: row.get(0)?,
+                schedule_id: row.get(1)?,
+                process_id: row.get(2)?,
+                action: ScheduleAction::parse(&action).unwrap_or(ScheduleAction::Start),
+                scheduled_at: row.get(4)?,
+                fired_at: row.get(5)?,
+                missed: row.get::<_, i32>(6)? != 0,
+                status: ScheduleRunStatus::parse(&status).unwrap_or(ScheduleRunStatus::Failed),
+                message: row.get(8)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::process_manager::{ProcessMetrics, ProcessMetricsEvent};
+
+    const MINUTE_MS: i64 = 60 * 1000;
+    const HOUR_MS: i64 = 60 * MINUTE_MS;
+    /// 对齐到 15 分钟的起始时间
+    const T0: i64 = 1_800_000_000_000 - 1_800_000_000_000 % (15 * MINUTE_MS);
+
+    fn metrics_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_process_metrics_table(&conn).unwrap();
+        conn
+    }
+
+    fn sample(conn: &Connection, id: &str, sampled_at: i64, cpu_percent: f64, rss_bytes: u64) {
+        let event = ProcessMetricsEvent {
+            id: id.to_string(),
+            pid: 1,
+            metrics: ProcessMetrics {
+                sampled_at,
+                cpu_percent,
+                rss_bytes,
+                threads: 2,
+                processes: 1,
+                read_bytes_per_sec: cpu_percent * 10.0,
+                write_bytes_per_sec: 0.0,
+            },
+        };
+        save_process_metrics(conn, &[event]).unwrap();
+    }
+
+    fn load(conn: &Connection, id: &str, resolution: MetricsResolution) -> Vec<ProcessMetrics> {
+        load_process_metrics(conn, id, resolution, 0, i64::MAX).unwrap()
+    }
+
+    fn samples(conn: &Connection, resolution: MetricsResolution, timestamp: i64) -> i64 {
+        conn.query_row(
+            "SELECT samples FROM process_metrics WHERE resolution = ?1 AND timestamp = ?2",
+            params![resolution.bucket_secs(), timestamp],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn rolls_up_finished_minutes() {
+        let conn = metrics_db();
+        sample(&conn, "p", T0, 10.0, 100);
+        sample(&conn, "p", T0 + 10_000, 20.0, 200);
+        sample(&conn, "p", T0 + 20_000, 30.0, 300);
+        // 当前分钟尚未结束，不汇总
+        sample(&conn, "p", T0 + MINUTE_MS + 1000, 90.0, 900);
+        sample(&conn, "q", T0 + 5000, 50.0, 500);
+
+        compact_process_metrics(&conn, T0 + MINUTE_MS + 5000).unwrap();
+        let minutes = load(&conn, "p", MetricsResolution::Minute);
+        assert_eq!(minutes.len(), 1);
+        assert_eq!(minutes[0].sampled_at, T0);
+        assert_eq!(minutes[0].cpu_percent, 20.0);
+        assert_eq!(minutes[0].rss_bytes, 200);
+        assert_eq!(minutes[0].read_bytes_per_sec, 200.0);
+        assert_eq!(samples(&conn, MetricsResolution::Minute, T0), 3);
+        assert_eq!(load(&conn, "q", MetricsResolution::Minute).len(), 1);
+        // 原始采样在保留期内不删除
+        assert_eq!(load(&conn, "p", MetricsResolution::Raw).len(), 4);
+
+        // 重复汇总不会重复计入，已汇总时间段之后新的采样在下次汇总
+        compact_process_metrics(&conn, T0 + MINUTE_MS + 5000).unwrap();
+        assert_eq!(load(&conn, "p", MetricsResolution::Minute), minutes);
+        compact_process_metrics(&conn, T0 + 2 * MINUTE_MS).unwrap();
+        let minutes = load(&conn, "p", MetricsResolution::Minute);
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[1].cpu_percent, 90.0);
+    }
+
+    #[test]
+    fn fifteen_minute_rollup_is_weighted_by_samples() {
+        let conn = metrics_db();
+        sample(&conn, "p", T0, 10.0, 100);
+        for offset in [0, 1000, 2000] {
+            sample(&conn, "p", T0 + MINUTE_MS + offset, 30.0, 500);
+        }
+        compact_process_metrics(&conn, T0 + 15 * MINUTE_MS + 1000).unwrap();
+
+        let quarters = load(&conn, "p", MetricsResolution::FifteenMinutes);
+        assert_eq!(quarters.len(), 1);
+        assert_eq!(quarters[0].sampled_at, T0);
+        assert_eq!(quarters[0].cpu_percent, 25.0);
+        assert_eq!(quarters[0].rss_bytes, 400);
+        assert_eq!(samples(&conn, MetricsResolution::FifteenMinutes, T0), 4);
+    }
+
+    #[test]
+    fn expired_rows_are_deleted_after_rollup() {
+        let conn = metrics_db();
+        let now = T0 + 30 * 24 * HOUR_MS;
+        sample(&conn, "p", now - 2 * HOUR_MS, 10.0, 100);
+        sample(&conn, "p", now - 25 * HOUR_MS, 20.0, 200);
+        sample(&conn, "p", now - 31 * 24 * HOUR_MS, 30.0, 300);
+        compact_process_metrics(&conn, now).unwrap();
+
+        assert!(load(&conn, "p", MetricsResolution::Raw).is_empty());
+        let minutes = load(&conn, "p", MetricsResolution::Minute);
+        assert_eq!(minutes.len(), 1);
+        assert_eq!(minutes[0].sampled_at, now - 2 * HOUR_MS);
+        let quarters = load(&conn, "p", MetricsResolution::FifteenMinutes);
+        let timestamps: Vec<i64> = quarters.iter().map(|m| m.sampled_at).collect();
+        assert_eq!(timestamps, [now - 25 * HOUR_MS, now - 2 * HOUR_MS]);
+    }
+
+    #[test]
+    fn resolution_for_range() {
+        let now = T0;
+        assert_eq!(
+            MetricsResolution::for_range(now - HOUR_MS, now),
+            MetricsResolution::Raw
+        );
+        assert_eq!(
+            MetricsResolution::for_range(now - 2 * HOUR_MS, now),
+            MetricsResolution::Minute
+        );
+        assert_eq!(
+            MetricsResolution::for_range(now - 48 * HOUR_MS, now),
+            MetricsResolution::FifteenMinutes
+        );
+    }
+}
@@ -1,13 +1,22 @@
-import { Component, inject, OnInit } from "@angular/core";
+import { Component, computed, inject, OnInit } from "@angular/core";
+import { MatProgressBarModule } from "@angular/material/progress-bar";
 import { RouterOutlet } from "@angular/router";
+import { TranslateModule } from "@ngx-translate/core";
 import { SidebarComponent } from "./components/sidebar/sidebar.component";
 import { TopbarComponent } from "./components/topbar/topbar.component";
+import { ProcessService } from "./services/process.service";
 import { ThemeService } from "./services/theme.service";
 import { TitlebarSyncService } from "./services/titlebar-sync.service";
 
 @Component({
   selector: "app-root",
-  imports: [RouterOutlet, SidebarComponent, TopbarComponent],
+  imports: [
+    RouterOutlet,
+    SidebarComponent,
+    TopbarComponent,
+    MatProgressBarModule,
+    TranslateModule,
+  ],
   templateUrl: "./app.component.html",
   styleUrl: "./app.component.scss",
 })
@@ -15,6 +24,13 @@ export class AppComponent implements OnInit {
   // 初始化主题服务
   private themeService = inject(ThemeService);
   private titlebarSyncService = inject(TitlebarSyncService);
+  private processService = inject(ProcessService);
+
+  // 退出应用时停止进程的进度，全部停止前显示遮罩
+  readonly shutdown = computed(() => {
+    const progress = this.processService.shutdownProgress();
+    return progress && !progress.done ? progress : null;
+  });
 
   ngOnInit(): void {
     this.titlebarSyncService.init();
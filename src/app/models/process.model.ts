@@ -1,12 +1,200 @@
 /** 进程启动模式 */
 export type ProcessMode = "fork" | "import";
 
-/** 进程状态 */
-export type ProcessStatus = "running" | "stopped" | "error";
+/** 进程类型：长期运行的服务或运行至结束的一次性任务 */
+export type ProcessKind = "service" | "task";
+
+/**
+ * 残留实例清理方式：none 不清理；exe-path 结束可执行文件路径相同的进程；
+ * pid-file 只结束上次记录的 PID（校验启动时间）
+ */
+export type StaleCleanup = "none" | "exe-path" | "pid-file";
+
+/** 钩子命令失败时：fail 中止后续钩子（及启动 / 停止），continue 忽略失败 */
+export type HookFailure = "fail" | "continue";
+
+/** 生命周期钩子命令，与进程使用相同的工作目录和环境变量 */
+export interface HookCommand {
+  command_type: CommandType;
+  command: string;
+  args: string[];
+  timeout_ms: number; // 超时时间（毫秒），超时视为失败
+  on_failure: HookFailure;
+}
+
+/** 资源限制（仅 Linux），未设置的项不限制 */
+export interface ResourceLimits {
+  max_memory_bytes?: number; // 最大内存（字节）
+  cpu_quota_percent?: number; // CPU 配额（百分比，100 表示一个核心）
+  max_open_files?: number; // 最多打开的文件数
+  max_processes?: number; // 最大进程数
+}
+
+/** 资源阈值规则的指标：rss 为常驻内存（字节），cpu 为 CPU 占用（百分比） */
+export type ThresholdMetric = "rss" | "cpu";
+
+/** 资源阈值规则触发后的操作 */
+export type ThresholdAction = "warn" | "restart" | "stop";
+
+/** 资源阈值规则：资源占用持续超过阈值一段时间后执行操作（仅 Linux） */
+export interface ThresholdRule {
+  metric: ThresholdMetric;
+  above: number; // 阈值，单位与指标相同
+  duration_secs: number; // 持续超过阈值的时长（秒）
+  action: ThresholdAction;
+}
+
+/** 资源阈值规则的一次触发 */
+export interface ThresholdTrigger {
+  triggered_at: number; // 秒
+  rule: ThresholdRule;
+  value: number; // 触发时的采样值
+}
+
+/** 进程资源占用（进程及其所有子孙进程的合计） */
+export interface ProcessMetrics {
+  sampled_at: number; // 采样时间（毫秒时间戳）
+  cpu_percent: number; // CPU 占用（百分比，100 表示占满一个核心）
+  rss_bytes: number; // 常驻内存（字节）
+  threads: number;
+  processes: number; // 进程数（包括子孙进程）
+  read_bytes_per_sec: number;
+  write_bytes_per_sec: number;
+}
+
+/** 进程资源占用事件 */
+export interface ProcessMetricsEvent {
+  id: string;
+  pid: number;
+  metrics: ProcessMetrics;
+}
+
+/**
+ * 资源占用历史的粒度（raw：原始采样，保留 1 小时；
+ * 1m / 15m：该时间段内的平均值，分别保留 1 天和 30 天）
+ */
+export type MetricsResolution = "raw" | "1m" | "15m";
+
+/** 伪终端窗口大小 */
+export interface PtySize {
+  rows: number;
+  cols: number;
+}
+
+/** 进程状态（completed：任务最近一次运行成功完成） */
+export type ProcessStatus =
+  | "running"
+  | "stopped"
+  | "restarting"
+  | "completed"
+  | "error";
 
 /** 命令类型 */
 export type CommandType = "executable" | "shell";
 
+/** 重启策略类型 */
+export type RestartPolicyKind = "no" | "on-failure" | "always" | "unless-stopped";
+
+/** 重启策略 */
+export interface RestartPolicy {
+  policy: RestartPolicyKind;
+  max_retries?: number; // 仅 on-failure：最大连续重试次数
+}
+
+/** 停止信号 */
+export type StopSignal = "sigterm" | "sigint" | "sighup" | "sigquit" | "sigkill";
+
+/** 进程停止方式 */
+export type StopOutcome = "signaled" | "force-killed" | "already-exited";
+
+/** 停止进程结果 */
+export interface StopResult {
+  id: string;
+  outcome: StopOutcome;
+  signal: StopSignal;
+  elapsed_ms: number; // 从发送信号到进程退出的耗时
+}
+
+/** 退出应用时停止进程的进度 */
+export interface ShutdownProgress {
+  total: number;
+  stopped: number;
+  pending: string[]; // 尚未停止的进程名称
+  elapsed_ms: number;
+  deadline_ms: number; // 总超时时间，超时后剩余进程被强制结束
+  done: boolean;
+}
+
+/** 进程退出原因 */
+export type ExitReason =
+  | "exited"
+  | "crashed"
+  | "user-stopped"
+  | "killed-by-timeout"
+  | "health-check-failed"
+  | "readiness-timeout"
+  | "app-shutdown"
+  | "hook-failed"
+  | "oom-killed"
+  | "threshold-exceeded";
+
+/** 进程最近一次退出的信息 */
+export interface ExitInfo {
+  code?: number; // 退出码，被信号终止时为空
+  signal?: number; // 终止进程的信号（仅 Unix）
+  exited_at: number;
+  reason: ExitReason;
+  duration_ms?: number; // 本次运行的时长（毫秒）
+}
+
+/** 进程的一次运行记录 */
+export interface ProcessRun {
+  id: number;
+  process_id: string;
+  started_at: number; // 秒
+  exited_at: number; // 秒
+  duration_ms: number;
+  code?: number;
+  signal?: number;
+  reason: ExitReason; // exited 即运行成功
+  threshold_triggers: ThresholdTrigger[]; // 本次运行中触发的资源阈值规则
+}
+
+/** 健康检查探测方式 */
+export type HealthProbe =
+  | { type: "tcp"; host?: string; port: number } // host 默认 127.0.0.1
+  | { type: "http"; url: string; expected_status?: number } // 仅支持 http，默认期望 200
+  | { type: "command"; command: string; expected_exit_code?: number }; // 默认期望退出码 0
+
+/** 健康检查配置 */
+export type HealthCheckConfig = HealthProbe & {
+  interval_ms: number; // 检查间隔（毫秒）
+  timeout_ms: number; // 单次检查超时（毫秒）
+  failure_threshold: number; // 连续失败多少次后视为不健康
+  restart_on_failure: boolean; // 不健康时是否重启进程
+};
+
+/** 就绪条件 */
+export type ReadinessProbe =
+  | { type: "output-match"; pattern: string } // 输出中出现匹配正则表达式的行
+  | { type: "tcp"; host?: string; port: number } // host 默认 127.0.0.1
+  | { type: "health-check" }; // 使用 health_check 配置的探测方式
+
+/** 就绪检测配置 */
+export type ReadinessConfig = ReadinessProbe & {
+  startup_timeout_ms: number; // 启动超时（毫秒），超时未就绪视为启动失败
+  kill_on_timeout: boolean; // 启动超时后是否结束进程
+};
+
+/** 健康状态 */
+export type HealthState = "healthy" | "unhealthy" | "unknown";
+
+/** 崩溃循环隔离信息 */
+export interface QuarantineInfo {
+  reason: string;
+  since: number;
+}
+
 /** 进程配置 */
 export interface ProcessConfig {
   id: string;
@@ -17,10 +205,35 @@ export interface ProcessConfig {
   working_dir: string;
   source_path?: string;
   env: Record<string, string>;
-  auto_restart: boolean;
+  kind: ProcessKind;
+  restart_policy: RestartPolicy;
+  success_exit_codes: number[]; // 除 0 外视为正常退出的退出码
   auto_start: boolean;
   created_at: number;
   command_type: CommandType; // 命令类型：executable 或 shell
+  restart_delay_ms: number; // 自动重启初始退避延迟（毫秒）
+  restart_max_delay_ms: number; // 自动重启最大退避延迟（毫秒）
+  crash_loop_max_exits: number; // 时间窗口内允许的最大退出次数（0 表示不检测）
+  crash_loop_window_secs: number; // 崩溃循环检测时间窗口（秒）
+  stop_signal: StopSignal; // 停止进程时发送的信号
+  stop_timeout_ms: number; // 等待进程退出的时间（毫秒），超时后强制结束
+  health_check?: HealthCheckConfig; // 健康检查（可选）
+  readiness?: ReadinessConfig; // 就绪条件（可选），未设置时启动后即视为就绪
+  depends_on: string[]; // 依赖的进程 ID，启动前先启动依赖
+  wait_for_dependencies: boolean; // 启动前是否等待依赖就绪
+  start_order: number; // 自动启动优先级，数值小的先启动
+  start_delay_ms: number; // 自动启动时启动前等待的时间（毫秒）
+  stale_cleanup: StaleCleanup; // 启动前清理上次遗留的实例
+  survive_app_exit: boolean; // 退出应用时保持运行（输出写入日志文件）
+  pre_start: HookCommand[]; // 启动前依次执行的钩子
+  post_start: HookCommand[]; // 启动后（设置了就绪条件时为就绪后）执行的钩子
+  pre_stop: HookCommand[]; // 停止前依次执行的钩子
+  post_stop: HookCommand[]; // 停止后依次执行的钩子
+  use_pty: boolean; // 在伪终端中运行（仅 Unix），标准输出和标准错误合并
+  pty_size: PtySize; // 伪终端初始窗口大小
+  resource_limits: ResourceLimits; // 资源限制
+  threshold_rules: ThresholdRule[]; // 资源阈值规则
+  task_timeout_ms: number; // 任务：依赖它的进程等待其结束的最长时间（毫秒）
 }
 
 /** 进程信息（运行时状态） */
@@ -32,14 +245,49 @@ export interface ProcessInfo {
   args: string[];
   working_dir: string;
   source_path?: string;
+  kind: ProcessKind;
   status: ProcessStatus;
   pid?: number;
-  auto_restart: boolean;
+  adopted: boolean; // 应用重启后重新接管的进程（无法获取其输出和退出码）
+  restart_policy: RestartPolicy;
+  success_exit_codes: number[];
   auto_start: boolean;
   started_at?: number;
   created_at: number;
   has_output: boolean;
   command_type: CommandType; // 命令类型：executable 或 shell
+  restart_delay_ms: number;
+  restart_max_delay_ms: number;
+  restart_attempts: number; // 连续自动重启次数
+  next_restart_at?: number; // 下一次自动重启时间（毫秒时间戳）
+  crash_loop_max_exits: number;
+  crash_loop_window_secs: number;
+  quarantine?: QuarantineInfo; // 崩溃循环隔离信息
+  stop_signal: StopSignal;
+  stop_timeout_ms: number;
+  last_exit?: ExitInfo; // 最近一次退出的信息
+  health_check?: HealthCheckConfig;
+  health: HealthState; // 未运行或未配置健康检查时为 unknown
+  health_failures: number; // 连续健康检查失败次数
+  readiness?: ReadinessConfig;
+  depends_on: string[];
+  wait_for_dependencies: boolean;
+  start_order: number;
+  start_delay_ms: number;
+  stale_cleanup: StaleCleanup;
+  survive_app_exit: boolean;
+  detached: boolean; // 当前实例以脱离模式运行
+  pre_start: HookCommand[];
+  post_start: HookCommand[];
+  pre_stop: HookCommand[];
+  post_stop: HookCommand[];
+  use_pty: boolean;
+  pty_size: PtySize;
+  resource_limits: ResourceLimits;
+  threshold_rules: ThresholdRule[];
+  task_timeout_ms: number;
+  metrics?: ProcessMetrics; // 运行中的进程最近一次采样的资源占用（仅 Linux）
+  threshold_triggers: ThresholdTrigger[]; // 运行中的进程本次运行中触发的资源阈值规则
 }
 
 /** Fork 模式添加进程参数 */
@@ -48,7 +296,7 @@ export interface AddProcessForkParams {
   working_dir: string; // 必选：工作目录
   executable_path?: string; // 可选：可执行程序路径
   args: string[];
-  auto_restart: boolean;
+  restart_policy: RestartPolicy;
   auto_start: boolean;
   command_type: CommandType; // 命令类型
 }
@@ -59,7 +307,7 @@ export interface AddProcessImportParams {
   source_folder: string;
   executable_path?: string; // 可选：可执行程序路径
   args: string[];
-  auto_restart: boolean;
+  restart_policy: RestartPolicy;
   auto_start: boolean;
   command_type: CommandType; // 命令类型
 }
@@ -71,7 +319,7 @@ export interface UpdateProcessParams {
   working_dir?: string; // 可选：工作目录
   executable_path?: string; // 可选：可执行程序路径
   args: string[];
-  auto_restart: boolean;
+  restart_policy: RestartPolicy;
   auto_start: boolean;
   command_type: CommandType; // 命令类型
 }
@@ -79,10 +327,76 @@ export interface UpdateProcessParams {
 /** 进程输出事件 */
 export interface ProcessOutputEvent {
   id: string;
-  output_type: "stdout" | "stderr";
+  // hook：生命周期钩子的输出；stdin：发送给进程的输入
+  output_type: "stdout" | "stderr" | "hook" | "stdin";
   line: string;
   timestamp: number;
 }
 
+/** 进程退出事件 */
+export interface ProcessExitedEvent {
+  id: string;
+  pid: number;
+  exit: ExitInfo;
+}
+
+/** 进程生命周期事件 */
+export type ProcessEvent =
+  | { type: "starting" }
+  | { type: "start-failed"; error: string }
+  | { type: "started"; pid: number }
+  | { type: "ready" }
+  | { type: "stopping"; signal: StopSignal }
+  | ({ type: "exited"; pid: number } & ExitInfo)
+  | { type: "restarting"; attempt: number; delay_ms: number }
+  | { type: "restart-cancelled" }
+  | { type: "quarantined"; reason: string; exits: number; window_secs: number }
+  | { type: "quarantine-cleared" }
+  | { type: "threshold-exceeded"; rule: ThresholdRule; value: number }
+  | { type: "health-changed"; health: HealthState; message?: string }
+  | { type: "config-changed" }
+  | { type: "removed" };
+
+/** 进程生命周期事件消息（process-event 事件负载） */
+export type ProcessEventMessage = ProcessEvent & {
+  id: string;
+  timestamp: number; // 毫秒
+};
+
 /** 进程输出行 */
 export type ProcessOutputLine = [number, string, string]; // [timestamp, type, line]
+
+/** 定时任务执行的操作 */
+export type ScheduleAction = "start" | "stop" | "restart";
+
+/** 错过触发时间时的处理策略 */
+export type MissedRunPolicy = "run-once" | "skip";
+
+/** 进程定时任务 */
+export interface ProcessSchedule {
+  id: string;
+  process_id: string;
+  cron: string; // 分 时 日 月 周（本地时区）
+  action: ScheduleAction;
+  enabled: boolean;
+  missed_run_policy: MissedRunPolicy;
+  last_checked_at: number; // 秒
+  next_run_at?: number; // 秒，禁用时为空
+  created_at: number;
+}
+
+/** 定时任务触发结果 */
+export type ScheduleRunStatus = "success" | "failed" | "skipped";
+
+/** 定时任务触发记录 */
+export interface ScheduleRun {
+  id: number;
+  schedule_id: string;
+  process_id: string;
+  action: ScheduleAction;
+  scheduled_at: number; // 秒
+  fired_at: number; // 秒
+  missed: boolean; // 错过触发时间后的补执行或跳过
+  status: ScheduleRunStatus;
+  message?: string;
+}
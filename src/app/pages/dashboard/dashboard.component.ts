@@ -76,7 +76,7 @@ export class DashboardComponent implements OnInit, OnDestroy {
                 working_dir: result.workingDir,
                 executable_path: result.executablePath,
                 args: result.args,
-                auto_restart: result.autoRestart,
+                restart_policy: result.restartPolicy,
                 auto_start: result.autoStart,
                 command_type: result.commandType,
               });
@@ -86,7 +86,7 @@ export class DashboardComponent implements OnInit, OnDestroy {
                 source_folder: result.path,
                 executable_path: result.executablePath,
                 args: result.args,
-                auto_restart: result.autoRestart,
+                restart_policy: result.restartPolicy,
                 auto_start: result.autoStart,
                 command_type: result.commandType,
               });
@@ -112,6 +112,10 @@ export class DashboardComponent implements OnInit, OnDestroy {
         return "play_circle";
       case "stopped":
         return "stop_circle";
+      case "restarting":
+        return "autorenew";
+      case "completed":
+        return "task_alt";
       case "error":
         return "error";
       default:
@@ -119,6 +123,18 @@ export class DashboardComponent implements OnInit, OnDestroy {
     }
   }
 
+  /** 格式化内存大小 */
+  formatBytes(bytes: number): string {
+    const units = ["B", "KB", "MB", "GB"];
+    let value = bytes;
+    let unit = 0;
+    while (value >= 1024 && unit < units.length - 1) {
+      value /= 1024;
+      unit++;
+    }
+    return `${value.toFixed(unit === 0 ? 0 : 1)} ${units[unit]}`;
+  }
+
   getRunningTime(startedAt: number): string {
     // 使用 tick 信号触发更新
     const now = Math.floor(this.tick() / 1000);
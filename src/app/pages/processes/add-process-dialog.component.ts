@@ -11,9 +11,14 @@ import { MatButtonModule } from "@angular/material/button";
 import { MatCheckboxModule } from "@angular/material/checkbox";
 import { MatIconModule } from "@angular/material/icon";
 import { MatRadioModule } from "@angular/material/radio";
+import { MatSelectModule } from "@angular/material/select";
 import { TranslateModule } from "@ngx-translate/core";
 import { open } from "@tauri-apps/plugin-dialog";
-import { CommandType } from "../../models/process.model";
+import {
+  CommandType,
+  RestartPolicy,
+  RestartPolicyKind,
+} from "../../models/process.model";
 
 export interface AddProcessDialogData {
   mode: "fork" | "import";
@@ -26,7 +31,7 @@ export interface AddProcessDialogResult {
   executablePath?: string;
   path: string; // 保留用于 import 模式
   args: string[];
-  autoRestart: boolean;
+  restartPolicy: RestartPolicy;
   autoStart: boolean;
   commandType: CommandType;
 }
@@ -43,6 +48,7 @@ export interface AddProcessDialogResult {
     MatCheckboxModule,
     MatIconModule,
     MatRadioModule,
+    MatSelectModule,
     TranslateModule,
   ],
   template: `
@@ -183,9 +189,16 @@ export interface AddProcessDialogResult {
           <mat-hint>{{ "process.argsHint" | translate }}</mat-hint>
         </mat-form-field>
 
-        <mat-checkbox formControlName="autoRestart">
-          {{ "process.autoRestart" | translate }}
-        </mat-checkbox>
+        <mat-form-field appearance="outline">
+          <mat-label>{{ "process.restartPolicy" | translate }}</mat-label>
+          <mat-select formControlName="restartPolicy">
+            @for (policy of restartPolicies; track policy.value) {
+              <mat-option [value]="policy.value">
+                {{ policy.label | translate }}
+              </mat-option>
+            }
+          </mat-select>
+        </mat-form-field>
 
         <mat-checkbox formControlName="autoStart">
           {{ "process.autoStart" | translate }}
@@ -277,13 +290,20 @@ export class AddProcessDialogComponent {
   private readonly dialogRef = inject(MatDialogRef<AddProcessDialogComponent>);
   readonly data = inject<AddProcessDialogData>(MAT_DIALOG_DATA);
 
+  readonly restartPolicies: { value: RestartPolicyKind; label: string }[] = [
+    { value: "no", label: "process.restartPolicies.no" },
+    { value: "on-failure", label: "process.restartPolicies.onFailure" },
+    { value: "always", label: "process.restartPolicies.always" },
+    { value: "unless-stopped", label: "process.restartPolicies.unlessStopped" },
+  ];
+
   form = this.fb.group({
     name: ["", Validators.required],
     workingDir: [""], // fork 模式必填，在构造函数中动态设置验证
     executablePath: [""], // fork 模式可选
     path: [""], // import 模式必填
     args: [""],
-    autoRestart: [false],
+    restartPolicy: ["no" as RestartPolicyKind],
     autoStart: [false],
     commandType: ["executable" as CommandType], // 默认为 executable
   });
@@ -345,7 +365,7 @@ export class AddProcessDialogComponent {
         executablePath: value.executablePath || undefined,
         path: value.path || "",
         args: value.args ? value.args.split(/\s+/).filter(Boolean) : [],
-        autoRestart: value.autoRestart || false,
+        restartPolicy: { policy: value.restartPolicy || "no" },
         autoStart: value.autoStart || false,
         commandType: (value.commandType as CommandType) || "executable",
       };
@@ -11,9 +11,15 @@ import { MatButtonModule } from "@angular/material/button";
 import { MatCheckboxModule } from "@angular/material/checkbox";
 import { MatIconModule } from "@angular/material/icon";
 import { MatRadioModule } from "@angular/material/radio";
+import { MatSelectModule } from "@angular/material/select";
 import { TranslateModule } from "@ngx-translate/core";
 import { open } from "@tauri-apps/plugin-dialog";
-import { ProcessInfo, CommandType } from "../../models/process.model";
+import {
+  ProcessInfo,
+  CommandType,
+  RestartPolicy,
+  RestartPolicyKind,
+} from "../../models/process.model";
 
 export interface EditProcessDialogData {
   process: ProcessInfo;
@@ -25,7 +31,7 @@ export interface EditProcessDialogResult {
   workingDir?: string;
   executablePath?: string;
   args: string[];
-  autoRestart: boolean;
+  restartPolicy: RestartPolicy;
   autoStart: boolean;
   commandType: CommandType;
 }
@@ -42,6 +48,7 @@ export interface EditProcessDialogResult {
     MatCheckboxModule,
     MatIconModule,
     MatRadioModule,
+    MatSelectModule,
     TranslateModule,
   ],
   template: `
@@ -168,9 +175,16 @@ export interface EditProcessDialogResult {
           <mat-hint>{{ "process.argsHint" | translate }}</mat-hint>
         </mat-form-field>
 
-        <mat-checkbox formControlName="autoRestart">
-          {{ "process.autoRestart" | translate }}
-        </mat-checkbox>
+        <mat-form-field appearance="outline">
+          <mat-label>{{ "process.restartPolicy" | translate }}</mat-label>
+          <mat-select formControlName="restartPolicy">
+            @for (policy of restartPolicies; track policy.value) {
+              <mat-option [value]="policy.value">
+                {{ policy.label | translate }}
+              </mat-option>
+            }
+          </mat-select>
+        </mat-form-field>
 
         <mat-checkbox formControlName="autoStart">
           {{ "process.autoStart" | translate }}
@@ -258,12 +272,19 @@ export class EditProcessDialogComponent {
   private readonly dialogRef = inject(MatDialogRef<EditProcessDialogComponent>);
   readonly data = inject<EditProcessDialogData>(MAT_DIALOG_DATA);
 
+  readonly restartPolicies: { value: RestartPolicyKind; label: string }[] = [
+    { value: "no", label: "process.restartPolicies.no" },
+    { value: "on-failure", label: "process.restartPolicies.onFailure" },
+    { value: "always", label: "process.restartPolicies.always" },
+    { value: "unless-stopped", label: "process.restartPolicies.unlessStopped" },
+  ];
+
   form = this.fb.group({
     name: [this.data.process.name, Validators.required],
     workingDir: [this.data.process.working_dir],
     executablePath: [this.data.process.command], // command 字段存储可执行程序路径
     args: [this.data.process.args.join(" ")],
-    autoRestart: [this.data.process.auto_restart],
+    restartPolicy: [this.data.process.restart_policy.policy],
     autoStart: [this.data.process.auto_start],
     commandType: [
       (this.data.process.command_type || "executable") as CommandType,
@@ -288,6 +309,12 @@ export class EditProcessDialogComponent {
     }
   }
 
+  /** 策略类型未变时保留原有的重试次数等参数 */
+  private buildRestartPolicy(kind: RestartPolicyKind): RestartPolicy {
+    const current = this.data.process.restart_policy;
+    return current.policy === kind ? current : { policy: kind };
+  }
+
   submit() {
     if (this.form.valid) {
       const value = this.form.value;
@@ -297,7 +324,7 @@ export class EditProcessDialogComponent {
         workingDir: value.workingDir || undefined,
         executablePath: value.executablePath || undefined,
         args: value.args ? value.args.split(/\s+/).filter(Boolean) : [],
-        autoRestart: value.autoRestart || false,
+        restartPolicy: this.buildRestartPolicy(value.restartPolicy || "no"),
         autoStart: value.autoStart || false,
         commandType: (value.commandType as CommandType) || "executable",
       };
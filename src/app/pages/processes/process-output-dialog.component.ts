@@ -11,6 +11,8 @@ import {
 import { MAT_DIALOG_DATA, MatDialogModule } from "@angular/material/dialog";
 import { MatButtonModule } from "@angular/material/button";
 import { MatIconModule } from "@angular/material/icon";
+import { MatFormFieldModule } from "@angular/material/form-field";
+import { MatInputModule } from "@angular/material/input";
 import { TranslateModule } from "@ngx-translate/core";
 import { ProcessService } from "../../services/process.service";
 import { ProcessOutputEvent } from "../../models/process.model";
@@ -29,7 +31,14 @@ interface OutputLine {
 @Component({
   selector: "app-process-output-dialog",
   standalone: true,
-  imports: [MatDialogModule, MatButtonModule, MatIconModule, TranslateModule],
+  imports: [
+    MatDialogModule,
+    MatButtonModule,
+    MatIconModule,
+    MatFormFieldModule,
+    MatInputModule,
+    TranslateModule,
+  ],
   template: `
     <h2 mat-dialog-title>
       {{ data.name }} - {{ "process.output" | translate }}
@@ -37,7 +46,12 @@ interface OutputLine {
     <mat-dialog-content>
       <div class="output-container" #outputContainer>
         @for (line of outputLines(); track $index) {
-          <div class="output-line" [class.stderr]="line.type === 'stderr'">
+          <div
+            class="output-line"
+            [class.stderr]="line.type === 'stderr'"
+            [class.hook]="line.type === 'hook'"
+            [class.stdin]="line.type === 'stdin'"
+          >
             <span class="timestamp">{{ formatTime(line.timestamp) }}</span>
             <span class="content">{{ line.line }}</span>
           </div>
@@ -46,6 +60,27 @@ interface OutputLine {
           <div class="empty-output">{{ "process.noOutput" | translate }}</div>
         }
       </div>
+      <mat-form-field appearance="outline" class="input-field">
+        <mat-icon matPrefix>chevron_right</mat-icon>
+        <input
+          matInput
+          [placeholder]="'process.inputPlaceholder' | translate"
+          [value]="inputText()"
+          (input)="inputText.set($any($event.target).value)"
+          (keydown.enter)="sendInput()"
+        />
+        <button
+          mat-icon-button
+          matSuffix
+          [disabled]="!inputText()"
+          (click)="sendInput()"
+        >
+          <mat-icon>send</mat-icon>
+        </button>
+        @if (inputError()) {
+          <mat-hint class="input-error">{{ inputError() }}</mat-hint>
+        }
+      </mat-form-field>
     </mat-dialog-content>
     <mat-dialog-actions align="end">
       <button mat-button (click)="clearOutput()">
@@ -86,6 +121,14 @@ interface OutputLine {
         &.stderr {
           color: var(--mat-sys-error);
         }
+
+        &.hook {
+          color: var(--mat-sys-tertiary);
+        }
+
+        &.stdin {
+          color: var(--mat-sys-primary);
+        }
       }
 
       .timestamp {
@@ -99,6 +142,16 @@ interface OutputLine {
         flex: 1;
       }
 
+      .input-field {
+        width: 100%;
+        margin-top: 12px;
+        font-family: monospace;
+      }
+
+      .input-error {
+        color: var(--mat-sys-error);
+      }
+
       .empty-output {
         color: var(--mat-sys-on-surface-variant);
         text-align: center;
@@ -132,6 +185,8 @@ export class ProcessOutputDialogComponent
   @ViewChild("outputContainer") outputContainer!: ElementRef<HTMLDivElement>;
 
   outputLines = signal<OutputLine[]>([]);
+  inputText = signal("");
+  inputError = signal("");
   private shouldScroll = true;
 
   ngOnInit() {
@@ -186,6 +241,21 @@ export class ProcessOutputDialogComponent
     this.outputLines.set([]);
   }
 
+  /** 向进程的标准输入发送一行命令 */
+  async sendInput() {
+    const text = this.inputText();
+    if (!text) {
+      return;
+    }
+    try {
+      await this.processService.sendProcessInput(this.data.id, text);
+      this.inputText.set("");
+      this.inputError.set("");
+    } catch (error) {
+      this.inputError.set(String(error));
+    }
+  }
+
   formatTime(timestamp: number): string {
     const date = new Date(timestamp);
     return date.toLocaleTimeString("en-US", { hour12: false });
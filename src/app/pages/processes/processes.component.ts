@@ -15,7 +15,7 @@ import { MatProgressSpinnerModule } from "@angular/material/progress-spinner";
 import { MatDialog, MatDialogModule } from "@angular/material/dialog";
 import { TranslateModule } from "@ngx-translate/core";
 import { ProcessService } from "../../services/process.service";
-import { ProcessInfo } from "../../models/process.model";
+import { ExitInfo, ProcessInfo } from "../../models/process.model";
 import {
   AddProcessDialogComponent,
   AddProcessDialogResult,
@@ -81,7 +81,7 @@ export class ProcessesComponent implements OnInit {
                 working_dir: result.workingDir,
                 executable_path: result.executablePath,
                 args: result.args,
-                auto_restart: result.autoRestart,
+                restart_policy: result.restartPolicy,
                 auto_start: result.autoStart,
                 command_type: result.commandType,
               });
@@ -91,7 +91,7 @@ export class ProcessesComponent implements OnInit {
                 source_folder: result.path,
                 executable_path: result.executablePath,
                 args: result.args,
-                auto_restart: result.autoRestart,
+                restart_policy: result.restartPolicy,
                 auto_start: result.autoStart,
                 command_type: result.commandType,
               });
@@ -121,7 +121,7 @@ export class ProcessesComponent implements OnInit {
               working_dir: result.workingDir,
               executable_path: result.executablePath,
               args: result.args,
-              auto_restart: result.autoRestart,
+              restart_policy: result.restartPolicy,
               auto_start: result.autoStart,
               command_type: result.commandType,
             });
@@ -148,6 +148,10 @@ export class ProcessesComponent implements OnInit {
     }
   }
 
+  async clearQuarantine(id: string) {
+    await this.processService.clearProcessQuarantine(id);
+  }
+
   async removeProcess(id: string) {
     await this.processService.removeProcess(id);
   }
@@ -158,10 +162,22 @@ export class ProcessesComponent implements OnInit {
         return "play_circle";
       case "stopped":
         return "stop_circle";
+      case "restarting":
+        return "autorenew";
+      case "completed":
+        return "task_alt";
       case "error":
         return "error";
       default:
         return "help";
     }
   }
+
+  /** 退出码或终止信号 */
+  getExitDetail(exit: ExitInfo): string {
+    if (exit.signal != null) {
+      return `(signal ${exit.signal})`;
+    }
+    return exit.code != null ? `(code ${exit.code})` : "";
+  }
 }
@@ -8,6 +8,7 @@ import {
 import { MatCardModule } from "@angular/material/card";
 import { MatFormFieldModule } from "@angular/material/form-field";
 import { MatIconModule } from "@angular/material/icon";
+import { MatInputModule } from "@angular/material/input";
 import { MatSelectModule } from "@angular/material/select";
 import { MatSlideToggleModule } from "@angular/material/slide-toggle";
 import { TranslateModule } from "@ngx-translate/core";
@@ -22,6 +23,7 @@ import { invoke } from "@tauri-apps/api/core";
     MatCardModule,
     MatFormFieldModule,
     MatIconModule,
+    MatInputModule,
     MatSelectModule,
     MatSlideToggleModule,
     TranslateModule,
@@ -36,11 +38,17 @@ export class SystemSettingsCardComponent implements OnInit {
 
   autoStartEnabled = signal(false);
   silentStartEnabled = signal(false);
+  autoStartDelaySecs = signal(0);
+  surviveAppExitEnabled = signal(false);
+  shutdownTimeoutSecs = signal(30);
   showPalettePanel = signal(false);
 
   async ngOnInit() {
     await this.loadAutoStartState();
     await this.loadSilentStartState();
+    await this.loadAutoStartDelay();
+    await this.loadSurviveAppExit();
+    await this.loadShutdownTimeout();
   }
 
   private async loadAutoStartState() {
@@ -63,6 +71,41 @@ export class SystemSettingsCardComponent implements OnInit {
     }
   }
 
+  private async loadAutoStartDelay() {
+    try {
+      const value = await invoke<string | null>("get_download_setting", {
+        key: "auto_start_delay_secs",
+      });
+      this.autoStartDelaySecs.set(Number(value) || 0);
+    } catch (e) {
+      console.error("Failed to load auto-start delay:", e);
+    }
+  }
+
+  private async loadSurviveAppExit() {
+    try {
+      const value = await invoke<string | null>("get_download_setting", {
+        key: "survive_app_exit",
+      });
+      this.surviveAppExitEnabled.set(value === "true");
+    } catch (e) {
+      console.error("Failed to load survive app exit setting:", e);
+    }
+  }
+
+  private async loadShutdownTimeout() {
+    try {
+      const value = await invoke<string | null>("get_download_setting", {
+        key: "shutdown_timeout_secs",
+      });
+      if (value !== null && value.trim() !== "") {
+        this.shutdownTimeoutSecs.set(Number(value) || 0);
+      }
+    } catch (e) {
+      console.error("Failed to load shutdown timeout:", e);
+    }
+  }
+
   async onAutoStartChange(enabled: boolean) {
     try {
       if (enabled) {
@@ -92,6 +135,46 @@ export class SystemSettingsCardComponent implements OnInit {
     }
   }
 
+  async onAutoStartDelayChange(value: string) {
+    const secs = Math.max(0, Math.floor(Number(value) || 0));
+    try {
+      await invoke("set_download_setting", {
+        key: "auto_start_delay_secs",
+        value: String(secs),
+      });
+      this.autoStartDelaySecs.set(secs);
+    } catch (e) {
+      console.error("Failed to save auto-start delay:", e);
+    }
+  }
+
+  async onSurviveAppExitChange(enabled: boolean) {
+    try {
+      await invoke("set_download_setting", {
+        key: "survive_app_exit",
+        value: enabled ? "true" : "false",
+      });
+      this.surviveAppExitEnabled.set(enabled);
+    } catch (e) {
+      console.error("Failed to toggle survive app exit:", e);
+      // 恢复原状态
+      this.surviveAppExitEnabled.set(!enabled);
+    }
+  }
+
+  async onShutdownTimeoutChange(value: string) {
+    const secs = Math.max(0, Math.floor(Number(value) || 0));
+    try {
+      await invoke("set_download_setting", {
+        key: "shutdown_timeout_secs",
+        value: String(secs),
+      });
+      this.shutdownTimeoutSecs.set(secs);
+    } catch (e) {
+      console.error("Failed to save shutdown timeout:", e);
+    }
+  }
+
   togglePalettePanel() {
     this.showPalettePanel.update((v) => !v);
   }
@@ -9,16 +9,30 @@ import {
   UpdateProcessParams,
   ProcessOutputEvent,
   ProcessOutputLine,
+  ProcessEventMessage,
+  ProcessMetrics,
+  ProcessMetricsEvent,
+  MetricsResolution,
+  StopResult,
+  ProcessRun,
+  ProcessSchedule,
+  ScheduleAction,
+  MissedRunPolicy,
+  ScheduleRun,
+  ShutdownProgress,
 } from "../models/process.model";
 
 @Injectable({ providedIn: "root" })
 export class ProcessService {
   private readonly _processes = signal<ProcessInfo[]>([]);
   private readonly _loading = signal(false);
+  private readonly _shutdownProgress = signal<ShutdownProgress | null>(null);
   private outputListeners = new Map<string, UnlistenFn>();
 
   readonly processes = this._processes.asReadonly();
   readonly loading = this._loading.asReadonly();
+  /** 退出应用时停止进程的进度，未在退出时为 null */
+  readonly shutdownProgress = this._shutdownProgress.asReadonly();
 
   readonly runningCount = computed(
     () => this._processes().filter((p) => p.status === "running").length,
@@ -33,9 +47,21 @@ export class ProcessService {
   }
 
   private async setupEventListener() {
-    await listen("process-status-changed", () => {
+    await listen<ProcessEventMessage>("process-event", () => {
       this.refresh();
     });
+    await listen<ShutdownProgress>("shutdown-progress", (event) => {
+      this._shutdownProgress.set(event.payload);
+    });
+    // 资源占用只更新对应进程，不重新加载列表
+    await listen<ProcessMetricsEvent>("process-metrics", (event) => {
+      const { id, pid, metrics } = event.payload;
+      this._processes.update((processes) =>
+        processes.map((p) =>
+          p.id === id && p.pid === pid ? { ...p, metrics } : p,
+        ),
+      );
+    });
   }
 
   async refresh(): Promise<void> {
@@ -57,7 +83,7 @@ export class ProcessService {
       workingDir: params.working_dir,
       executablePath: params.executable_path,
       args: params.args,
-      autoRestart: params.auto_restart,
+      restartPolicy: params.restart_policy,
       autoStart: params.auto_start,
       commandType: params.command_type,
     });
@@ -74,7 +100,7 @@ export class ProcessService {
       sourceFolder: params.source_folder,
       executablePath: params.executable_path,
       args: params.args,
-      autoRestart: params.auto_restart,
+      restartPolicy: params.restart_policy,
       autoStart: params.auto_start,
       commandType: params.command_type,
     });
@@ -90,7 +116,7 @@ export class ProcessService {
       workingDir: params.working_dir,
       executablePath: params.executable_path,
       args: params.args,
-      autoRestart: params.auto_restart,
+      restartPolicy: params.restart_policy,
       autoStart: params.auto_start,
       commandType: params.command_type,
     });
@@ -114,11 +140,97 @@ export class ProcessService {
     return info;
   }
 
-  async stopProcess(id: string): Promise<void> {
-    await invoke("stop_process", { id });
+  async stopProcess(id: string): Promise<StopResult> {
+    const result = await invoke<StopResult>("stop_process", { id });
+    await this.refresh();
+    return result;
+  }
+
+  /** 停止进程及所有依赖它的进程（按依赖关系逆序停止） */
+  async stopProcessWithDependents(id: string): Promise<StopResult[]> {
+    const results = await invoke<StopResult[]>("stop_process_with_dependents", {
+      id,
+    });
+    await this.refresh();
+    return results;
+  }
+
+  /** 解除崩溃循环隔离 */
+  async clearProcessQuarantine(id: string): Promise<void> {
+    await invoke("clear_process_quarantine", { id });
     await this.refresh();
   }
 
+  /** 获取进程最近几次运行的记录（最新的在前） */
+  async listProcessRuns(id: string): Promise<ProcessRun[]> {
+    return invoke<ProcessRun[]>("list_process_runs", { id });
+  }
+
+  /**
+   * 获取进程在时间范围内（毫秒时间戳）的资源占用历史，用于绘制图表。
+   * 未指定粒度时使用仍保留着该范围数据的最细粒度
+   */
+  async getProcessMetrics(
+    id: string,
+    from: number,
+    to: number,
+    resolution?: MetricsResolution,
+  ): Promise<ProcessMetrics[]> {
+    return invoke<ProcessMetrics[]>("get_process_metrics", {
+      id,
+      from,
+      to,
+      resolution,
+    });
+  }
+
+  /** 获取定时任务列表，可按进程筛选 */
+  async listSchedules(processId?: string): Promise<ProcessSchedule[]> {
+    return invoke<ProcessSchedule[]>("list_process_schedules", { processId });
+  }
+
+  /** 添加定时任务 */
+  async addSchedule(
+    processId: string,
+    cron: string,
+    action: ScheduleAction,
+    missedRunPolicy?: MissedRunPolicy,
+  ): Promise<ProcessSchedule> {
+    return invoke<ProcessSchedule>("add_process_schedule", {
+      processId,
+      cron,
+      action,
+      missedRunPolicy,
+    });
+  }
+
+  /** 修改定时任务 */
+  async updateSchedule(schedule: ProcessSchedule): Promise<ProcessSchedule> {
+    return invoke<ProcessSchedule>("update_process_schedule", {
+      id: schedule.id,
+      cron: schedule.cron,
+      action: schedule.action,
+      enabled: schedule.enabled,
+      missedRunPolicy: schedule.missed_run_policy,
+    });
+  }
+
+  /** 删除定时任务 */
+  async removeSchedule(id: string): Promise<void> {
+    await invoke("remove_process_schedule", { id });
+  }
+
+  /** 获取定时任务的触发记录（最新的在前） */
+  async listScheduleRuns(
+    processId?: string,
+    scheduleId?: string,
+  ): Promise<ScheduleRun[]> {
+    return invoke<ScheduleRun[]>("list_schedule_runs", {
+      processId,
+      scheduleId,
+    });
+  }
+
   async getProcess(id: string): Promise<ProcessInfo> {
     return invoke<ProcessInfo>("get_process", { id });
   }
@@ -133,6 +245,20 @@ export class ProcessService {
     await invoke("clear_process_output", { id });
   }
 
+  /** 向运行中的进程的标准输入发送一行文本 */
+  async sendProcessInput(id: string, text: string): Promise<void> {
+    await invoke("send_process_input", { id, text });
+  }
+
+  /** 调整在伪终端中运行的进程的窗口大小 */
+  async resizeProcessPty(
+    id: string,
+    rows: number,
+    cols: number,
+  ): Promise<void> {
+    await invoke("resize_process_pty", { id, rows, cols });
+  }
+
   /** 订阅进程输出事件 */
   async subscribeOutput(
     id: string,